@@ -1,22 +1,56 @@
-use halo_custom_field_builder::config::Config;
+use anyhow::Context;
+use clap::Parser;
+use halo_custom_field_builder::config::{Config, ConfigOverrides};
+use halo_custom_field_builder::domain::collision::ConflictPolicy;
+use halo_custom_field_builder::domain::deletion::{DeletionPlan, Removal};
+use halo_custom_field_builder::domain::import_result::ImportResults;
+use halo_custom_field_builder::domain::journal::Journal;
 use halo_custom_field_builder::domain::logging;
-use halo_custom_field_builder::inbound::readers::CsvReader;
+use halo_custom_field_builder::domain::models::custom_field::CustomField;
+use halo_custom_field_builder::domain::models::existing_field::ExistingField;
+use halo_custom_field_builder::domain::plan::Plan;
+use halo_custom_field_builder::inbound::cli::{Cli, Command, DryRunArgs, ImportArgs};
+use halo_custom_field_builder::inbound::manifest::ManifestReader;
+use halo_custom_field_builder::inbound::readers::SourceReader;
 use halo_custom_field_builder::inbound::screens::{RunMode, ScreenManager};
 use halo_custom_field_builder::outbound::auth::client::AuthClient;
+use halo_custom_field_builder::outbound::export;
 use halo_custom_field_builder::outbound::field_client::FieldClient;
-use log::{error, info};
-
-async fn run() -> anyhow::Result<()> {
-    logging::setup_logging()?;
-
-    info!("starting application...\n");
+use halo_custom_field_builder::outbound::rate_limiter::RateLimiter;
+use log::{error, info, warn};
+use std::path::Path;
+use std::sync::Arc;
 
+fn load_config(overrides: &ConfigOverrides) -> anyhow::Result<Config> {
     info!("loading configuration...");
-    let config = Config::load_from_env()?;
+    let config = Config::load(overrides)?;
+    if let Some(profile) = &config.profile {
+        info!(
+            "✓ using profile '{}'{}",
+            profile.name,
+            if profile.protected {
+                " (protected)"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(credentials) = &config.credentials {
+        info!("✓ client secret read from {}", credentials.secret_source);
+    }
     info!("✓ configuration loaded successfully\n");
 
+    Ok(config)
+}
+
+/// fetches the first token up front so bad credentials fail fast
+/// the client then keeps the token fresh for the rest of the run
+async fn authenticate(
+    config: &Config,
+    rate_limiter: Arc<RateLimiter>,
+) -> anyhow::Result<(AuthClient, String)> {
     info!("authenticating with api...");
-    let auth_client = AuthClient::new(config.clone());
+    let auth_client = AuthClient::new(config.clone(), rate_limiter);
 
     let token = auth_client.get_valid_token().await.map_err(|e| {
         error!("✗ authentication failed: {}", e);
@@ -26,44 +60,428 @@ async fn run() -> anyhow::Result<()> {
     info!("✓ authentication successful");
     info!("✓ token acquired and valid\n");
 
-    info!("reading csv file...");
-    let reader = CsvReader::new();
-    let fields = reader.read_fields(&config)?;
+    Ok((auth_client, token))
+}
+
+fn read_fields(config: &Config) -> anyhow::Result<Vec<CustomField>> {
+    info!("reading source file...");
+    let reader = SourceReader::new();
+    let fields = reader.read_fields(config)?;
     info!("✓ successfully validated {} fields\n", fields.len());
 
-    let screen_manager = ScreenManager::new(fields);
-    screen_manager.show_initial_stats(&token.split_whitespace().next().unwrap_or("unknown"))?;
+    Ok(fields)
+}
+
+/// authenticates unless a dry run has no credentials to do so
+/// returning the token type alongside the client for display
+async fn connect(
+    config: &Config,
+    dry_run: &DryRunArgs,
+) -> anyhow::Result<(FieldClient, Option<String>)> {
+    let rate_limiter = Arc::new(RateLimiter::new(config.request_budget));
+    let authenticated = if dry_run.dry_run && config.credentials.is_none() {
+        info!("no credentials configured, skipping authentication for dry run\n");
+        None
+    } else {
+        Some(authenticate(config, rate_limiter.clone()).await?)
+    };
+    let token_type = authenticated.as_ref().map(|(_, token)| {
+        token
+            .split_whitespace()
+            .next()
+            .unwrap_or("unknown")
+            .to_string()
+    });
+    let auth_client = authenticated.map(|(auth_client, _)| auth_client);
+
+    let field_client = if dry_run.dry_run {
+        FieldClient::dry_run(config.clone(), auth_client, rate_limiter)
+    } else {
+        FieldClient::new(
+            config.clone(),
+            auth_client.context("missing auth client")?,
+            rate_limiter,
+        )
+    };
+
+    Ok((field_client, token_type))
+}
+
+async fn list_existing_fields(field_client: &FieldClient) -> anyhow::Result<Vec<ExistingField>> {
+    info!("checking existing fields...");
+    let existing_fields = field_client.list_fields().await?;
+    info!("✓ found {} existing fields\n", existing_fields.len());
+
+    Ok(existing_fields)
+}
+
+fn write_dry_run_requests(field_client: &FieldClient, dry_run: &DryRunArgs) -> anyhow::Result<()> {
+    if field_client.is_dry_run() {
+        let requests = field_client.dry_run_requests();
+        export::write_dry_run_requests(&requests, dry_run.output.as_deref())?;
+        info!(
+            "✓ dry run recorded {} requests, nothing was sent",
+            requests.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// opens the import journal and reports what an interrupted run left behind
+fn open_journal(config: &Config, resume: bool) -> anyhow::Result<Journal> {
+    let journal = Journal::open(config.source_file_name()?, resume)?;
+    info!(
+        "✓ journaling to {} (source {})\n",
+        journal.path().display(),
+        &journal.source_hash()[..12]
+    );
+
+    if journal.is_resuming() {
+        for name in journal.unconfirmed() {
+            error!(
+                "? {} was sent by an interrupted run without a recorded outcome, check it on the instance",
+                name
+            );
+        }
+    }
+
+    Ok(journal)
+}
+
+/// writes to a protected profile need its name typed, even with `--yes`
+/// or passed as `confirmed_profile` when no one is there to type it
+/// a cancelled write is an error so scripts do not mistake it for success
+fn confirm_profile(
+    config: &Config,
+    screen_manager: &ScreenManager,
+    dry_run: &DryRunArgs,
+    confirmed_profile: Option<&str>,
+) -> anyhow::Result<()> {
+    match &config.profile {
+        Some(profile) if profile.protected && !dry_run.dry_run => {
+            let confirmed = match confirmed_profile {
+                Some(name) => name == profile.name,
+                None => screen_manager.confirm_protected_profile(profile)?,
+            };
+            if !confirmed {
+                anyhow::bail!("write to protected profile '{}' cancelled", profile.name);
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// runs the given mode or asks for one when none is given
+async fn run_fields(
+    config: &Config,
+    run_mode: Option<RunMode>,
+    args: &ImportArgs,
+    confirmed_profile: Option<&str>,
+) -> anyhow::Result<()> {
+    let (field_client, token_type) = connect(config, &args.dry_run).await?;
+    let fields = read_fields(config)?;
+
+    let check_existing_fields = field_client.can_list_fields();
+    let existing_fields = if check_existing_fields {
+        list_existing_fields(&field_client).await?
+    } else {
+        info!("no credentials configured, skipping existing field check\n");
+        Vec::new()
+    };
 
-    let field_client = FieldClient::new(config.clone(), token);
+    let screen_manager = ScreenManager::new(fields, existing_fields, args.on_conflict.into());
+    screen_manager.show_initial_stats(
+        config.profile.as_ref(),
+        token_type.as_deref(),
+        args.dry_run.dry_run,
+    )?;
+    if check_existing_fields {
+        screen_manager.show_collision_report();
+    }
+
+    let run_mode = match run_mode {
+        Some(run_mode) => run_mode,
+        None => screen_manager.get_run_mode()?,
+    };
+
+    if !matches!(run_mode, RunMode::Quit) {
+        confirm_profile(config, &screen_manager, &args.dry_run, confirmed_profile)?;
+    }
 
-    match screen_manager.get_run_mode()? {
+    let journal = match run_mode {
+        RunMode::Quit => None,
+        _ if field_client.is_dry_run() => None,
+        _ => Some(open_journal(config, args.resume)?),
+    };
+
+    match run_mode {
         RunMode::Import => {
             info!("\n{}", "=".repeat(80));
             info!("starting full import mode");
             info!("{}\n", "=".repeat(80));
 
-            let results = screen_manager.process_all_fields(&field_client).await?;
-            results.log_summary();
+            let results = screen_manager
+                .process_all_fields(&field_client, journal.as_ref())
+                .await?;
+            report_results(&results, &field_client)?;
         }
         RunMode::Debug => {
             info!("\n{}", "=".repeat(80));
             info!("starting debug mode");
             info!("{}\n", "=".repeat(80));
 
-            let results = screen_manager.debug_mode(&field_client).await?;
-            results.log_summary();
+            let results = screen_manager
+                .debug_mode(&field_client, journal.as_ref())
+                .await?;
+            report_results(&results, &field_client)?;
         }
         RunMode::Quit => {
             info!("program terminated by user");
         }
     }
 
+    write_dry_run_requests(&field_client, &args.dry_run)
+}
+
+/// compares the source file with the fields on the instance
+async fn prepare_plan(
+    config: &Config,
+    dry_run: &DryRunArgs,
+) -> anyhow::Result<(FieldClient, ScreenManager, Plan)> {
+    let (field_client, _) = connect(config, dry_run).await?;
+    if !field_client.can_list_fields() {
+        anyhow::bail!("planning needs credentials to list the fields on the instance");
+    }
+
+    let fields = read_fields(config)?;
+    let existing_fields = list_existing_fields(&field_client).await?;
+
+    let screen_manager = ScreenManager::new(fields, existing_fields, ConflictPolicy::default());
+    let plan = screen_manager.plan();
+    screen_manager.show_plan(&plan);
+
+    Ok((field_client, screen_manager, plan))
+}
+
+async fn plan(config: &Config) -> anyhow::Result<()> {
+    prepare_plan(config, &DryRunArgs::default()).await?;
+    Ok(())
+}
+
+/// `confirmed` skips the confirmation prompt
+async fn apply(
+    config: &Config,
+    dry_run: &DryRunArgs,
+    confirmed: bool,
+    confirmed_profile: Option<&str>,
+) -> anyhow::Result<()> {
+    let (field_client, screen_manager, plan) = prepare_plan(config, dry_run).await?;
+
+    if plan.is_empty() && plan.conflicts() > 0 {
+        warn!(
+            "✗ nothing to apply, {} conflict(s) listed in the plan need fixing in halo or the source file",
+            plan.conflicts()
+        );
+        return Ok(());
+    }
+    if plan.is_empty() {
+        info!("✓ instance already matches the source file, nothing to apply");
+        return Ok(());
+    }
+
+    if !confirmed && !dry_run.dry_run && !screen_manager.confirm_apply(&plan)? {
+        anyhow::bail!("apply cancelled by user");
+    }
+    confirm_profile(config, &screen_manager, dry_run, confirmed_profile)?;
+
+    info!("\n{}", "=".repeat(80));
+    info!("applying plan");
+    info!("{}\n", "=".repeat(80));
+
+    let results = screen_manager.apply_plan(&plan, &field_client).await?;
+    report_results(&results, &field_client)?;
+
+    write_dry_run_requests(&field_client, dry_run)
+}
+
+/// halo cannot be asked whether a field holds data
+/// so deleting, which loses it, has to be asked for explicitly
+fn removal(archive: bool, allow_data: bool) -> anyhow::Result<Removal> {
+    match (archive, allow_data) {
+        (true, _) => Ok(Removal::Archive),
+        (false, true) => Ok(Removal::Delete),
+        (false, false) => anyhow::bail!(
+            "deleting loses any data the fields hold and halo cannot be checked for it, \
+             pass --archive to relabel the fields instead or --allow-data to delete them anyway"
+        ),
+    }
+}
+
+/// removes the fields named in `manifest`
+/// refusing any this tool did not create unless `allow_untracked` is set
+async fn delete(
+    config: &Config,
+    manifest: &Path,
+    dry_run: &DryRunArgs,
+    confirmed: bool,
+    allow_untracked: bool,
+    removal: Removal,
+    confirmed_profile: Option<&str>,
+) -> anyhow::Result<()> {
+    let (field_client, _) = connect(config, dry_run).await?;
+    if !field_client.can_list_fields() {
+        anyhow::bail!("deleting needs credentials to resolve field names to ids");
+    }
+
+    info!("reading removal manifest...");
+    let entries = ManifestReader::new().read_entries(manifest)?;
+    info!("✓ found {} fields\n", entries.len());
+
+    let existing_fields = list_existing_fields(&field_client).await?;
+    let created = if allow_untracked {
+        None
+    } else {
+        Some(logging::created_fields()?)
+    };
+
+    let deletion_plan = DeletionPlan::new(&entries, &existing_fields, created.as_ref(), removal);
+    let screen_manager = ScreenManager::new(Vec::new(), existing_fields, ConflictPolicy::default());
+    screen_manager.show_deletion_plan(&deletion_plan);
+
+    if deletion_plan.deletions() == 0 {
+        info!("✓ nothing to remove");
+        return Ok(());
+    }
+
+    if !confirmed && !dry_run.dry_run && !screen_manager.confirm_deletion(&deletion_plan)? {
+        anyhow::bail!("removal cancelled by user");
+    }
+    confirm_profile(config, &screen_manager, dry_run, confirmed_profile)?;
+
+    let results = screen_manager
+        .delete_fields(&deletion_plan, &field_client)
+        .await?;
+    report_results(&results, &field_client)?;
+
+    write_dry_run_requests(&field_client, dry_run)
+}
+
+/// prints the summary and records what a real run changed
+fn report_results(results: &ImportResults, field_client: &FieldClient) -> anyhow::Result<()> {
+    results.log_summary();
+
+    if !field_client.is_dry_run() {
+        let results_file = logging::save_results(results)?;
+        info!("✓ results saved to {}", results_file.display());
+    }
+
+    Ok(())
+}
+
+/// reports every invalid row without authenticating
+fn validate(overrides: &ConfigOverrides) -> anyhow::Result<()> {
+    let source_file_name = Config::load_source_file_name(overrides)?;
+    let source_settings = Config::load_source_settings(overrides)?;
+
+    info!("validating {}...", source_file_name);
+    let report = SourceReader::new().validate(&source_file_name, &source_settings)?;
+    report.log_summary();
+
+    let fields = report.into_fields()?;
+    info!("✓ successfully validated {} fields\n", fields.len());
+
+    Ok(())
+}
+
+/// writes the payloads without authenticating or needing connection settings
+fn export(overrides: &ConfigOverrides, output: Option<&Path>) -> anyhow::Result<()> {
+    let source_file_name = Config::load_source_file_name(overrides)?;
+    let source_settings = Config::load_source_settings(overrides)?;
+
+    info!("reading {}...", source_file_name);
+    let fields = SourceReader::new().read_source(&source_file_name, &source_settings)?;
+    info!("✓ successfully validated {} fields\n", fields.len());
+
+    export::write_payloads(&fields, output)?;
+    info!("✓ exported {} field payloads", fields.len());
+
+    Ok(())
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    logging::setup_logging(cli.writes_to_stdout())?;
+
+    info!("starting application...\n");
+
+    let overrides = cli.config_overrides();
+    let confirmed_profile = cli.confirm_profile.as_deref();
+
+    match cli.command {
+        None => {
+            run_fields(
+                &load_config(&overrides)?,
+                None,
+                &ImportArgs::default(),
+                confirmed_profile,
+            )
+            .await?
+        }
+        Some(Command::Import { args }) => {
+            run_fields(
+                &load_config(&overrides)?,
+                Some(RunMode::Import),
+                &args,
+                confirmed_profile,
+            )
+            .await?
+        }
+        Some(Command::Debug { args }) => {
+            run_fields(
+                &load_config(&overrides)?,
+                Some(RunMode::Debug),
+                &args,
+                confirmed_profile,
+            )
+            .await?
+        }
+        Some(Command::Validate) => validate(&overrides)?,
+        Some(Command::Plan) => plan(&load_config(&overrides)?).await?,
+        Some(Command::Apply { dry_run, yes }) => {
+            apply(&load_config(&overrides)?, &dry_run, yes, confirmed_profile).await?
+        }
+        Some(Command::Delete {
+            manifest,
+            dry_run,
+            yes,
+            allow_untracked,
+            archive,
+            allow_data,
+        }) => {
+            let removal = removal(archive, allow_data)?;
+            delete(
+                &load_config(&overrides)?,
+                &manifest,
+                &dry_run,
+                yes,
+                allow_untracked,
+                removal,
+                confirmed_profile,
+            )
+            .await?
+        }
+        Some(Command::Export { output }) => export(&overrides, output.as_deref())?,
+    }
+
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    if let Err(e) = run().await {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli).await {
         error!("\n✗ error: {}", e);
         std::process::exit(1);
     }
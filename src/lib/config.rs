@@ -1,44 +1,921 @@
+pub mod error;
+pub mod profile;
+pub mod secret;
+
 use anyhow::Context;
-use url::Url;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use url::{Host, Url};
+
+use crate::config::error::{ConfigErrors, InvalidConfig};
+use crate::config::profile::{ActiveProfile, DEFAULT_PROFILES_FILE, Profile, ProfilesFile};
+use crate::config::secret::{SecretReference, SecretSource};
+use crate::outbound::rate_limiter::DEFAULT_REQUEST_BUDGET;
+use crate::outbound::retry::DEFAULT_MAX_ATTEMPTS;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub base_url: Url,
+    /// derived from the base url unless `AUTH_URL` points at a separate authorisation server
     pub token_url: Url,
+    /// derived from the base url unless `API_URL` points at a separate resource server
     pub api_url: Url,
+    /// missing when only running a dry run
+    pub credentials: Option<Credentials>,
+    /// oauth scope requested with the token, halo grants its default when missing
+    pub scope: Option<String>,
+    /// needed by hosted multi-tenant instances
+    pub tenant: Option<String>,
+    /// missing for commands that never read a source file
+    pub source_file_name: Option<String>,
+    /// how tabular source files are read
+    pub source_settings: SourceSettings,
+    /// tries per request before a transient failure is given up on
+    pub max_attempts: u32,
+    /// requests allowed in any 5 minute window
+    pub request_budget: u32,
+    /// missing when settings come from the environment alone
+    pub profile: Option<ActiveProfile>,
+}
+
+/// settings for reading csv files and workbooks
+#[derive(Debug, Clone)]
+pub struct SourceSettings {
+    /// workbook sheet to read, the first sheet when missing
+    pub sheet: Option<String>,
+    /// separates selection options within one cell
+    pub option_delimiter: char,
+}
+
+impl Default for SourceSettings {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            option_delimiter: DEFAULT_OPTION_DELIMITER,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Credentials {
     pub client_id: String,
     pub client_secret: String,
-    pub source_file_name: String,
+    /// where the secret was read from, safe to show unlike the secret itself
+    pub secret_source: SecretSource,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"<redacted>")
+            .field("secret_source", &self.secret_source)
+            .finish()
+    }
+}
+
+/// values given on the command line
+/// taking precedence over the environment
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub env_file: Option<PathBuf>,
+    pub base_url: Option<String>,
+    pub source_file_name: Option<String>,
+    pub source_sheet: Option<String>,
+    pub option_delimiter: Option<String>,
+    pub max_attempts: Option<u32>,
+    pub request_budget: Option<u32>,
+    pub profile: Option<String>,
+    pub profiles_file: Option<PathBuf>,
 }
 
 const BASE_URL_KEY: &str = "BASE_URL";
+const AUTH_URL_KEY: &str = "AUTH_URL";
+const API_URL_KEY: &str = "API_URL";
 const CLIENT_ID_KEY: &str = "CLIENT_ID";
 const CLIENT_SECRET_KEY: &str = "CLIENT_SECRET";
+const CLIENT_SECRET_FILE_KEY: &str = "CLIENT_SECRET_FILE";
+const CLIENT_SECRET_COMMAND_KEY: &str = "CLIENT_SECRET_COMMAND";
+const CLIENT_SECRET_KEYRING_KEY: &str = "CLIENT_SECRET_KEYRING";
+const SCOPE_KEY: &str = "SCOPE";
+const TENANT_KEY: &str = "TENANT";
 const SOURCE_FILE_NAME_KEY: &str = "SOURCE_FILE_NAME";
+const SOURCE_SHEET_KEY: &str = "SOURCE_SHEET";
+const OPTION_DELIMITER_KEY: &str = "OPTION_DELIMITER";
+const MAX_ATTEMPTS_KEY: &str = "MAX_ATTEMPTS";
+const REQUEST_BUDGET_KEY: &str = "REQUEST_BUDGET";
+const PROFILE_KEY: &str = "PROFILE";
+const PROFILES_FILE_KEY: &str = "PROFILES_FILE";
 
-const TOKEN_URL_PATH: &str = "auth/token";
+const AUTH_URL_PATH: &str = "auth";
+const TOKEN_URL_PATH: &str = "token";
 const API_URL_PATH: &str = "api";
 
+const DEFAULT_OPTION_DELIMITER: char = ',';
+
 impl Config {
     pub fn load_from_env() -> anyhow::Result<Self> {
-        dotenvy::dotenv().context("failed to load environment")?;
+        let exported = Self::secret_exported();
+        let env_file = dotenvy::dotenv().context("failed to load environment")?;
+        Ok(Self::from_env(
+            &ConfigOverrides::default(),
+            None,
+            Self::env_secret_source(exported, Some(env_file)),
+        )?)
+    }
+
+    /// loads the given env file or `.env` when present
+    /// variables already set in the environment are kept
+    /// so the tool can run from cron jobs and pipelines without one
+    /// a selected profile takes precedence over the environment
+    pub fn load(overrides: &ConfigOverrides) -> anyhow::Result<Self> {
+        let exported = Self::secret_exported();
+        let env_file = Self::load_env_file(overrides)?;
+        let profile = Self::load_profile(overrides)?;
+        Ok(Self::from_env(
+            overrides,
+            profile,
+            Self::env_secret_source(exported, env_file),
+        )?)
+    }
+
+    /// checked before any env file is loaded, since those never override the environment
+    fn secret_exported() -> bool {
+        std::env::var_os(CLIENT_SECRET_KEY).is_some()
+    }
+
+    /// where a `CLIENT_SECRET` variable came from
+    fn env_secret_source(exported: bool, env_file: Option<PathBuf>) -> SecretSource {
+        match env_file {
+            Some(path) if !exported => SecretSource::EnvFile(path),
+            _ => SecretSource::Environment,
+        }
+    }
+
+    /// the profile named by `--profile` or `PROFILE`, if any
+    fn load_profile(
+        overrides: &ConfigOverrides,
+    ) -> anyhow::Result<Option<(ActiveProfile, Profile)>> {
+        let Some(name) = overrides
+            .profile
+            .clone()
+            .or_else(|| Self::optional_from_env(PROFILE_KEY))
+        else {
+            return Ok(None);
+        };
+
+        let path = match &overrides.profiles_file {
+            Some(path) => path.clone(),
+            None => Self::optional_from_env(PROFILES_FILE_KEY)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_PROFILES_FILE)),
+        };
+        let profiles = ProfilesFile::load(Path::new(&path))?;
+        let profile = profiles.profile(&name)?.clone();
+
+        let active = ActiveProfile {
+            name,
+            protected: profile.protected,
+        };
+        Ok(Some((active, profile)))
+    }
+
+    /// resolves only the source file
+    /// for commands that never contact halo
+    pub fn load_source_file_name(overrides: &ConfigOverrides) -> anyhow::Result<String> {
+        Self::load_env_file(overrides)?;
+        let (key, source_file_name) = Self::source_file_name_from_env(overrides)
+            .ok_or_else(|| InvalidConfig::Missing {
+                key: SOURCE_FILE_NAME_KEY.to_string(),
+            })
+            .map_err(ConfigErrors::from)?;
+        Self::check_source_file(&key, &source_file_name).map_err(ConfigErrors::from)?;
+
+        Ok(source_file_name)
+    }
+
+    /// resolves only the settings for reading the source file
+    /// expects the env file to be loaded by `load_source_file_name` first
+    pub fn load_source_settings(
+        overrides: &ConfigOverrides,
+    ) -> Result<SourceSettings, ConfigErrors> {
+        let mut problems = Vec::new();
+        let source_settings = Self::source_settings_from_env(overrides, &mut problems);
+        if !problems.is_empty() {
+            return Err(ConfigErrors(problems));
+        }
+
+        Ok(source_settings)
+    }
 
-        let base_url = Url::try_from(std::env::var(BASE_URL_KEY)?.as_str())?;
-        let mut token_url = base_url.clone();
-        token_url.set_path(TOKEN_URL_PATH);
-        let mut api_url = base_url.clone();
-        api_url.set_path(API_URL_PATH);
-        let client_id = std::env::var(CLIENT_ID_KEY)?;
-        let client_secret = std::env::var(CLIENT_SECRET_KEY)?;
-        let source_file_name = std::env::var(SOURCE_FILE_NAME_KEY)?;
+    /// the env file that was loaded, if any
+    fn load_env_file(overrides: &ConfigOverrides) -> anyhow::Result<Option<PathBuf>> {
+        match &overrides.env_file {
+            Some(path) => {
+                dotenvy::from_path(path).with_context(|| {
+                    format!("failed to load environment from {}", path.display())
+                })?;
+                Ok(Some(path.clone()))
+            }
+            None => Ok(dotenvy::dotenv().ok()),
+        }
+    }
+
+    /// the source file and the key it was given by
+    fn source_file_name_from_env(overrides: &ConfigOverrides) -> Option<(String, String)> {
+        match &overrides.source_file_name {
+            Some(source_file_name) => Some(("--source".to_string(), source_file_name.clone())),
+            None => Self::optional_from_env(SOURCE_FILE_NAME_KEY)
+                .map(|source_file_name| (SOURCE_FILE_NAME_KEY.to_string(), source_file_name)),
+        }
+    }
+
+    fn source_settings_from_env(
+        overrides: &ConfigOverrides,
+        problems: &mut Vec<InvalidConfig>,
+    ) -> SourceSettings {
+        let sheet = match &overrides.source_sheet {
+            Some(sheet) => Self::unquoted("--sheet".to_string(), sheet.clone(), problems),
+            None => Self::optional_from_env(SOURCE_SHEET_KEY)
+                .and_then(|sheet| Self::unquoted(SOURCE_SHEET_KEY.to_string(), sheet, problems)),
+        };
+        let option_delimiter = match &overrides.option_delimiter {
+            Some(delimiter) => Some(("--option-delimiter".to_string(), delimiter.clone())),
+            None => Self::optional_from_env(OPTION_DELIMITER_KEY)
+                .map(|delimiter| (OPTION_DELIMITER_KEY.to_string(), delimiter)),
+        }
+        .and_then(|(key, delimiter)| {
+            Self::check_delimiter(&key, &delimiter)
+                .map_err(|problem| problems.push(problem))
+                .ok()
+        })
+        .unwrap_or(DEFAULT_OPTION_DELIMITER);
+
+        SourceSettings {
+            sheet,
+            option_delimiter,
+        }
+    }
+
+    /// quotes and backslashes are taken by the option syntax itself
+    fn check_delimiter(key: &str, delimiter: &str) -> Result<char, InvalidConfig> {
+        let mut chars = delimiter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if !matches!(c, '"' | '\\') => Ok(c),
+            _ => Err(InvalidConfig::Delimiter {
+                key: key.to_string(),
+                value: delimiter.to_string(),
+            }),
+        }
+    }
+
+    /// checks every setting before giving up
+    /// so all problems are reported together
+    fn from_env(
+        overrides: &ConfigOverrides,
+        profile: Option<(ActiveProfile, Profile)>,
+        env_secret_source: SecretSource,
+    ) -> Result<Self, ConfigErrors> {
+        let mut problems = Vec::new();
+        let (active_profile, settings) = profile.unzip();
+        let settings = settings.unwrap_or_default();
+        let profile_key = |field: &str| match &active_profile {
+            Some(profile) => format!("{} in profile '{}'", field, profile.name),
+            None => field.to_string(),
+        };
+        // a selected profile describes the whole connection
+        // so a value it leaves out never comes from .env, which may point at another instance
+        let connection_from_env = |key: &str| match &active_profile {
+            Some(_) => None,
+            None => Self::optional_from_env(key),
+        };
+
+        let (base_url_key, base_url) = match (&overrides.base_url, &settings.base_url) {
+            (Some(base_url), _) => ("--base-url".to_string(), Some(base_url.clone())),
+            (None, Some(base_url)) => (profile_key("base_url"), Some(base_url.clone())),
+            (None, None) if active_profile.is_some() => (profile_key("base_url"), None),
+            (None, None) => (
+                BASE_URL_KEY.to_string(),
+                Self::optional_from_env(BASE_URL_KEY),
+            ),
+        };
+        let base_url = match base_url {
+            Some(base_url) => Self::check_base_url(&base_url_key, &base_url)
+                .map_err(|problem| problems.push(problem))
+                .ok(),
+            None => {
+                problems.push(InvalidConfig::Missing { key: base_url_key });
+                None
+            }
+        };
+
+        let auth_url = Self::endpoint_url(
+            settings
+                .auth_url
+                .as_ref()
+                .map(|url| (profile_key("auth_url"), url.clone())),
+            connection_from_env(AUTH_URL_KEY).map(|url| (AUTH_URL_KEY.to_string(), url)),
+            &mut problems,
+        );
+        let api_url = Self::endpoint_url(
+            settings
+                .api_url
+                .as_ref()
+                .map(|url| (profile_key("api_url"), url.clone())),
+            connection_from_env(API_URL_KEY).map(|url| (API_URL_KEY.to_string(), url)),
+            &mut problems,
+        );
+
+        let (client_id_key, client_id) = match &settings.client_id {
+            Some(client_id) => (profile_key("client_id"), Some(client_id.clone())),
+            None if active_profile.is_some() => {
+                problems.push(InvalidConfig::Missing {
+                    key: profile_key("client_id"),
+                });
+                (profile_key("client_id"), None)
+            }
+            None => (
+                CLIENT_ID_KEY.to_string(),
+                Self::optional_from_env(CLIENT_ID_KEY),
+            ),
+        };
+        let secret = Self::secret_reference(&settings, active_profile.as_ref(), env_secret_source)
+            .unwrap_or_else(|problem| {
+                problems.push(problem);
+                None
+            });
+        let credentials = match (client_id, secret) {
+            (Some(client_id), Some((secret_key, secret))) => {
+                if Self::is_quoted(&client_id) {
+                    problems.push(InvalidConfig::Quoted { key: client_id_key });
+                }
+                match secret.resolve() {
+                    Ok((client_secret, _)) if Self::is_quoted(&client_secret) => {
+                        problems.push(InvalidConfig::Quoted { key: secret_key });
+                        None
+                    }
+                    Ok((client_secret, secret_source)) => Some(Credentials {
+                        client_id,
+                        client_secret,
+                        secret_source,
+                    }),
+                    Err(error) => {
+                        problems.push(InvalidConfig::Secret(error));
+                        None
+                    }
+                }
+            }
+            (None, None) => None,
+            (Some(_), None) => {
+                problems.push(InvalidConfig::Incomplete {
+                    key: client_id_key,
+                    missing: CLIENT_SECRET_KEY.to_string(),
+                });
+                None
+            }
+            // already reported as missing from the profile
+            (None, Some(_)) if active_profile.is_some() => None,
+            (None, Some((secret_key, _))) => {
+                problems.push(InvalidConfig::Incomplete {
+                    key: secret_key,
+                    missing: client_id_key,
+                });
+                None
+            }
+        };
+
+        let scope = match &settings.scope {
+            Some(scope) => Self::unquoted(profile_key("scope"), scope.clone(), &mut problems),
+            None => connection_from_env(SCOPE_KEY)
+                .and_then(|scope| Self::unquoted(SCOPE_KEY.to_string(), scope, &mut problems)),
+        };
+        let tenant = match &settings.tenant {
+            Some(tenant) => Self::unquoted(profile_key("tenant"), tenant.clone(), &mut problems),
+            None => connection_from_env(TENANT_KEY)
+                .and_then(|tenant| Self::unquoted(TENANT_KEY.to_string(), tenant, &mut problems)),
+        };
+
+        let source_file_name =
+            Self::source_file_name_from_env(overrides).and_then(|(key, source_file_name)| {
+                match Self::check_source_file(&key, &source_file_name) {
+                    Ok(()) => Some(source_file_name),
+                    Err(problem) => {
+                        problems.push(problem);
+                        None
+                    }
+                }
+            });
+        let source_settings = Self::source_settings_from_env(overrides, &mut problems);
+
+        let max_attempts = Self::count_from_env(
+            overrides.max_attempts,
+            MAX_ATTEMPTS_KEY,
+            DEFAULT_MAX_ATTEMPTS,
+        )
+        .unwrap_or_else(|problem| {
+            problems.push(problem);
+            DEFAULT_MAX_ATTEMPTS
+        });
+        let request_budget = Self::count_from_env(
+            overrides.request_budget,
+            REQUEST_BUDGET_KEY,
+            DEFAULT_REQUEST_BUDGET,
+        )
+        .unwrap_or_else(|problem| {
+            problems.push(problem);
+            DEFAULT_REQUEST_BUDGET
+        });
+
+        let base_url = match base_url {
+            Some(base_url) if problems.is_empty() => base_url,
+            _ => return Err(ConfigErrors(problems)),
+        };
+        let (token_url, api_url) = Self::endpoints(&base_url, auth_url, api_url);
 
         Ok(Self {
             base_url,
             token_url,
             api_url,
-            client_id,
-            client_secret,
+            credentials,
+            scope,
+            tenant,
             source_file_name,
+            source_settings,
+            max_attempts,
+            request_budget,
+            profile: active_profile,
         })
     }
+
+    /// the one configured way of getting the client secret and the key it was set by
+    /// secrets in a profile replace those in the environment rather than mixing with them
+    fn secret_reference(
+        settings: &Profile,
+        profile: Option<&ActiveProfile>,
+        env_secret_source: SecretSource,
+    ) -> Result<Option<(String, SecretReference)>, InvalidConfig> {
+        let mut candidates = Vec::new();
+
+        if let Some(profile) = profile {
+            let key = |field: &str| format!("{} in profile '{}'", field, profile.name);
+            if let Some(secret) = &settings.client_secret {
+                let source = SecretSource::Profile(profile.name.clone());
+                candidates.push((
+                    key("client_secret"),
+                    SecretReference::Value(secret.clone(), source),
+                ));
+            }
+            if let Some(path) = &settings.client_secret_file {
+                candidates.push((
+                    key("client_secret_file"),
+                    SecretReference::File(path.clone()),
+                ));
+            }
+            if let Some(command) = &settings.client_secret_command {
+                candidates.push((
+                    key("client_secret_command"),
+                    SecretReference::Command(command.clone()),
+                ));
+            }
+            if let Some(entry) = &settings.client_secret_keyring {
+                candidates.push((
+                    key("client_secret_keyring"),
+                    SecretReference::Keyring(entry.clone()),
+                ));
+            }
+        }
+
+        // a profile without a secret is incomplete rather than completed from .env
+        if profile.is_none() {
+            if let Ok(secret) = std::env::var(CLIENT_SECRET_KEY) {
+                candidates.push((
+                    CLIENT_SECRET_KEY.to_string(),
+                    SecretReference::Value(secret, env_secret_source),
+                ));
+            }
+            if let Some(path) = Self::optional_from_env(CLIENT_SECRET_FILE_KEY) {
+                candidates.push((
+                    CLIENT_SECRET_FILE_KEY.to_string(),
+                    SecretReference::File(path.into()),
+                ));
+            }
+            if let Some(command) = Self::optional_from_env(CLIENT_SECRET_COMMAND_KEY) {
+                candidates.push((
+                    CLIENT_SECRET_COMMAND_KEY.to_string(),
+                    SecretReference::Command(command),
+                ));
+            }
+            if let Some(entry) = Self::optional_from_env(CLIENT_SECRET_KEYRING_KEY) {
+                candidates.push((
+                    CLIENT_SECRET_KEYRING_KEY.to_string(),
+                    SecretReference::Keyring(entry),
+                ));
+            }
+        }
+
+        if candidates.len() > 1 {
+            return Err(InvalidConfig::ConflictingSecrets {
+                keys: candidates.into_iter().map(|(key, _)| key).collect(),
+            });
+        }
+
+        Ok(candidates.pop())
+    }
+
+    /// catches the api or auth url being pasted in place of the instance url
+    fn check_base_url(key: &str, base_url: &str) -> Result<Url, InvalidConfig> {
+        let url = Self::check_url(key, base_url)?;
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default();
+        if let Some(index) = segments
+            .iter()
+            .position(|segment| segment.eq_ignore_ascii_case("auth"))
+        {
+            return Err(InvalidConfig::AuthUrl {
+                key: key.to_string(),
+                suggestion: Self::url_with_segments(&url, &segments[..index]),
+            });
+        }
+        if segments
+            .last()
+            .is_some_and(|segment| segment.eq_ignore_ascii_case("api"))
+        {
+            return Err(InvalidConfig::TrailingApi {
+                key: key.to_string(),
+                suggestion: Self::url_with_segments(&url, &segments[..segments.len() - 1]),
+            });
+        }
+
+        Ok(url)
+    }
+
+    /// an optional `AUTH_URL` or `API_URL`, preferring the profile value
+    fn endpoint_url(
+        profile_value: Option<(String, String)>,
+        env_value: Option<(String, String)>,
+        problems: &mut Vec<InvalidConfig>,
+    ) -> Option<Url> {
+        let (key, url) = profile_value.or(env_value)?;
+
+        Self::check_url(&key, &url)
+            .map_err(|problem| problems.push(problem))
+            .ok()
+    }
+
+    /// https only, except on this machine for local proxies
+    fn check_url(key: &str, url: &str) -> Result<Url, InvalidConfig> {
+        if Self::is_quoted(url) {
+            return Err(InvalidConfig::Quoted {
+                key: key.to_string(),
+            });
+        }
+
+        let parsed = Url::parse(url).map_err(|reason| InvalidConfig::MalformedUrl {
+            key: key.to_string(),
+            reason,
+        })?;
+        if parsed.scheme() != "https" && !(parsed.scheme() == "http" && Self::is_loopback(&parsed))
+        {
+            return Err(InvalidConfig::InsecureUrl {
+                key: key.to_string(),
+                url: url.to_string(),
+            });
+        }
+
+        Ok(parsed)
+    }
+
+    /// the token and api urls, from the overrides when given and otherwise below `base_url`
+    fn endpoints(base_url: &Url, auth_url: Option<Url>, api_url: Option<Url>) -> (Url, Url) {
+        let auth_url = auth_url.unwrap_or_else(|| Self::join_path(base_url, AUTH_URL_PATH));
+        // the token url itself is accepted too, since halo shows both
+        let token_url = if auth_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .is_some_and(|segment| segment.eq_ignore_ascii_case(TOKEN_URL_PATH))
+        {
+            auth_url
+        } else {
+            Self::join_path(&auth_url, TOKEN_URL_PATH)
+        };
+        let api_url = match api_url {
+            Some(api_url) => Self::join_path(&api_url, ""),
+            None => Self::join_path(base_url, API_URL_PATH),
+        };
+
+        (token_url, api_url)
+    }
+
+    /// `path` added after any path `url` already has
+    /// so instances served from a sub-path behind a proxy keep it
+    pub(crate) fn join_path(url: &Url, path: &str) -> Url {
+        let mut joined = url.clone();
+        let base = url.path().trim_end_matches('/');
+        if path.is_empty() {
+            joined.set_path(base);
+        } else {
+            joined.set_path(&format!("{}/{}", base, path));
+        }
+        joined.set_query(None);
+        joined.set_fragment(None);
+        joined
+    }
+
+    fn is_loopback(url: &Url) -> bool {
+        match url.host() {
+            Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+            Some(Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        }
+    }
+
+    /// `url` cut back to `segments`, used to suggest the instance url
+    fn url_with_segments(url: &Url, segments: &[&str]) -> String {
+        let mut suggestion = url.clone();
+        suggestion.set_path(&segments.join("/"));
+        suggestion.set_query(None);
+        suggestion.set_fragment(None);
+        suggestion.as_str().trim_end_matches('/').to_string()
+    }
+
+    fn check_source_file(key: &str, source_file_name: &str) -> Result<(), InvalidConfig> {
+        if Self::is_quoted(source_file_name) {
+            return Err(InvalidConfig::Quoted {
+                key: key.to_string(),
+            });
+        }
+
+        let path = PathBuf::from(source_file_name);
+        match File::open(&path) {
+            Ok(_) => Ok(()),
+            Err(reason) if reason.kind() == ErrorKind::NotFound => {
+                Err(InvalidConfig::SourceFileMissing { path })
+            }
+            Err(reason) => Err(InvalidConfig::SourceFileUnreadable { path, reason }),
+        }
+    }
+
+    /// windows `set` and some ci systems keep quotes as part of the value
+    fn is_quoted(value: &str) -> bool {
+        let value = value.trim();
+        value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+    }
+
+    /// `value`, unless it is quoted
+    fn unquoted(key: String, value: String, problems: &mut Vec<InvalidConfig>) -> Option<String> {
+        if Self::is_quoted(&value) {
+            problems.push(InvalidConfig::Quoted { key });
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// unset and blank values are both treated as missing
+    fn optional_from_env(key: &str) -> Option<String> {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// a positive whole number from the command line, the environment or `default`
+    fn count_from_env(
+        overridden: Option<u32>,
+        key: &str,
+        default: u32,
+    ) -> Result<u32, InvalidConfig> {
+        let count = match overridden {
+            Some(count) => count,
+            None => match std::env::var(key) {
+                Ok(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|_| InvalidConfig::NotANumber {
+                        key: key.to_string(),
+                        value,
+                    })?,
+                Err(_) => default,
+            },
+        };
+        if count == 0 {
+            return Err(InvalidConfig::TooSmall {
+                key: key.to_string(),
+            });
+        }
+
+        Ok(count)
+    }
+
+    pub fn source_file_name(&self) -> anyhow::Result<&str> {
+        self.source_file_name.as_deref().ok_or_else(|| {
+            anyhow::anyhow!("missing source file: {} is required", SOURCE_FILE_NAME_KEY)
+        })
+    }
+
+    pub fn credentials(&self) -> anyhow::Result<&Credentials> {
+        self.credentials.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "missing credentials: {} and {} are required",
+                CLIENT_ID_KEY,
+                CLIENT_SECRET_KEY
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    fn endpoints(
+        base_url: &str,
+        auth_url: Option<&str>,
+        api_url: Option<&str>,
+    ) -> (String, String) {
+        let (token_url, api_url) =
+            Config::endpoints(&url(base_url), auth_url.map(url), api_url.map(url));
+        (token_url.to_string(), api_url.to_string())
+    }
+
+    #[test]
+    fn join_path_keeps_the_existing_path() {
+        assert_eq!(
+            Config::join_path(&url("https://example.com/halo"), "api").as_str(),
+            "https://example.com/halo/api"
+        );
+        assert_eq!(
+            Config::join_path(&url("https://example.com/halo/"), "api").as_str(),
+            "https://example.com/halo/api"
+        );
+    }
+
+    #[test]
+    fn join_path_onto_a_host_root_has_a_single_slash() {
+        assert_eq!(
+            Config::join_path(&url("https://api.example.com"), "fieldinfo").as_str(),
+            "https://api.example.com/fieldinfo"
+        );
+        let api_url = Config::join_path(&url("https://api.example.com/"), "");
+        assert_eq!(
+            Config::join_path(&api_url, "fieldinfo").as_str(),
+            "https://api.example.com/fieldinfo"
+        );
+    }
+
+    #[test]
+    fn join_path_drops_query_and_fragment() {
+        assert_eq!(
+            Config::join_path(&url("https://example.com/halo?x=1#top"), "api").as_str(),
+            "https://example.com/halo/api"
+        );
+    }
+
+    #[test]
+    fn endpoints_are_derived_below_the_base_url() {
+        assert_eq!(
+            endpoints("https://example.com/halo", None, None),
+            (
+                "https://example.com/halo/auth/token".to_string(),
+                "https://example.com/halo/api".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn endpoints_use_the_overrides() {
+        assert_eq!(
+            endpoints(
+                "https://example.com",
+                Some("https://auth.example.com/auth"),
+                Some("https://api.example.com")
+            ),
+            (
+                "https://auth.example.com/auth/token".to_string(),
+                "https://api.example.com/".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn an_auth_url_may_already_be_the_token_url() {
+        let (token_url, _) = endpoints(
+            "https://example.com",
+            Some("https://auth.example.com/auth/Token"),
+            None,
+        );
+        assert_eq!(token_url, "https://auth.example.com/auth/Token");
+    }
+
+    #[test]
+    fn check_base_url_accepts_an_instance_url() {
+        let base_url = Config::check_base_url("BASE_URL", "https://example.com/halo").unwrap();
+        assert_eq!(base_url.as_str(), "https://example.com/halo");
+    }
+
+    #[test]
+    fn check_base_url_rejects_a_trailing_api() {
+        assert!(matches!(
+            Config::check_base_url("BASE_URL", "https://example.com/halo/api/"),
+            Err(InvalidConfig::TrailingApi { suggestion, .. })
+                if suggestion == "https://example.com/halo"
+        ));
+    }
+
+    #[test]
+    fn check_base_url_rejects_a_pasted_auth_url() {
+        assert!(matches!(
+            Config::check_base_url("BASE_URL", "https://example.com/auth/token"),
+            Err(InvalidConfig::AuthUrl { suggestion, .. }) if suggestion == "https://example.com"
+        ));
+    }
+
+    #[test]
+    fn check_url_requires_https_away_from_this_machine() {
+        assert!(matches!(
+            Config::check_url("BASE_URL", "http://example.com"),
+            Err(InvalidConfig::InsecureUrl { .. })
+        ));
+        assert!(Config::check_url("BASE_URL", "http://127.0.0.1:8080").is_ok());
+        assert!(Config::check_url("BASE_URL", "http://localhost").is_ok());
+    }
+
+    #[test]
+    fn check_url_reports_quotes_and_malformed_urls() {
+        assert!(matches!(
+            Config::check_url("BASE_URL", "\"https://example.com\""),
+            Err(InvalidConfig::Quoted { key }) if key == "BASE_URL"
+        ));
+        assert!(matches!(
+            Config::check_url("--base-url", "example.com"),
+            Err(InvalidConfig::MalformedUrl { key, .. }) if key == "--base-url"
+        ));
+    }
+
+    #[test]
+    fn check_delimiter_takes_a_single_plain_character() {
+        assert_eq!(
+            Config::check_delimiter("OPTION_DELIMITER", "|").unwrap(),
+            '|'
+        );
+        for delimiter in ["", "||", "\"", "\\"] {
+            assert!(matches!(
+                Config::check_delimiter("OPTION_DELIMITER", delimiter),
+                Err(InvalidConfig::Delimiter { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn counts_must_be_positive() {
+        assert_eq!(
+            Config::count_from_env(Some(3), "MAX_ATTEMPTS", 4).unwrap(),
+            3
+        );
+        assert!(matches!(
+            Config::count_from_env(Some(0), "MAX_ATTEMPTS", 4),
+            Err(InvalidConfig::TooSmall { key }) if key == "MAX_ATTEMPTS"
+        ));
+    }
+
+    #[test]
+    fn check_source_file_reports_a_missing_file() {
+        assert!(matches!(
+            Config::check_source_file("--source", "no/such/fields.csv"),
+            Err(InvalidConfig::SourceFileMissing { .. })
+        ));
+        assert!(matches!(
+            Config::check_source_file("--source", "'fields.csv'"),
+            Err(InvalidConfig::Quoted { .. })
+        ));
+        assert!(Config::check_source_file("--source", "Cargo.toml").is_ok());
+    }
+
+    #[test]
+    fn every_problem_is_listed() {
+        let errors = ConfigErrors(vec![
+            InvalidConfig::Missing {
+                key: "BASE_URL".to_string(),
+            },
+            InvalidConfig::TooSmall {
+                key: "MAX_ATTEMPTS".to_string(),
+            },
+        ]);
+
+        assert_eq!(
+            errors.to_string(),
+            "invalid configuration:\n  • BASE_URL is required\n  • MAX_ATTEMPTS must be at least 1"
+        );
+    }
 }
@@ -0,0 +1,66 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// one problem with the configuration
+/// `key` names where the value came from, such as `BASE_URL` or `--base-url`
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("{key} is required")]
+    Missing { key: String },
+    #[error("{key} is wrapped in quotes, remove them")]
+    Quoted { key: String },
+    #[error(
+        "{key} is not a valid url ({reason}), expected something like https://your-instance.halo.com"
+    )]
+    MalformedUrl {
+        key: String,
+        reason: url::ParseError,
+    },
+    #[error("{key} must use https, got '{url}'")]
+    InsecureUrl { key: String, url: String },
+    #[error("{key} should not end with /api, use '{suggestion}'")]
+    TrailingApi { key: String, suggestion: String },
+    #[error("{key} looks like the auth url, use the instance url '{suggestion}'")]
+    AuthUrl { key: String, suggestion: String },
+    #[error("{key} must be a whole number, got '{value}'")]
+    NotANumber { key: String, value: String },
+    #[error("{key} must be at least 1")]
+    TooSmall { key: String },
+    #[error("{key} must be a single character other than a quote or backslash, got '{value}'")]
+    Delimiter { key: String, value: String },
+    #[error("{key} is set without {missing}")]
+    Incomplete { key: String, missing: String },
+    #[error("{} are set, only one client secret source may be used", .keys.join(" and "))]
+    ConflictingSecrets { keys: Vec<String> },
+    #[error("{0:#}")]
+    Secret(anyhow::Error),
+    #[error("source file {} does not exist", .path.display())]
+    SourceFileMissing { path: PathBuf },
+    #[error("source file {} cannot be read: {reason}", .path.display())]
+    SourceFileUnreadable {
+        path: PathBuf,
+        reason: std::io::Error,
+    },
+}
+
+/// every problem found while loading the configuration
+/// reported together so they can all be fixed in one go
+#[derive(Debug, Error)]
+pub struct ConfigErrors(pub Vec<InvalidConfig>);
+
+impl Display for ConfigErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for problem in &self.0 {
+            write!(f, "\n  • {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<InvalidConfig> for ConfigErrors {
+    fn from(value: InvalidConfig) -> Self {
+        ConfigErrors(vec![value])
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILES_FILE: &str = "profiles.toml";
+
+/// named halo instances kept in one file
+/// so switching between dev, uat and production is a flag rather than a file swap
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilesFile {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// connection settings for one instance
+/// anything left out falls back to the environment
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub base_url: Option<String>,
+    /// authorisation server, when it is not on the base url
+    pub auth_url: Option<String>,
+    /// resource server, when it is not on the base url
+    pub api_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// file holding the secret, such as a docker or kubernetes secret mount
+    pub client_secret_file: Option<PathBuf>,
+    /// command printing the secret on stdout
+    pub client_secret_command: Option<String>,
+    /// os keyring entry holding the secret
+    pub client_secret_keyring: Option<String>,
+    pub scope: Option<String>,
+    pub tenant: Option<String>,
+    /// writes ask for the profile name to be typed before anything is sent
+    #[serde(default)]
+    pub protected: bool,
+}
+
+/// the profile a run was started with
+#[derive(Debug, Clone)]
+pub struct ActiveProfile {
+    pub name: String,
+    pub protected: bool,
+}
+
+impl ProfilesFile {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read profiles file {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("failed to parse profiles file {}", path.display()))
+    }
+
+    /// `name` is matched exactly and unknown names list the ones that exist
+    pub fn profile(&self, name: &str) -> anyhow::Result<&Profile> {
+        self.profiles.get(name).with_context(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                format!("unknown profile '{}': no profiles are defined", name)
+            } else {
+                format!(
+                    "unknown profile '{}': expected one of {}",
+                    name,
+                    known.join(", ")
+                )
+            }
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Context;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// keyring entries are looked up under this service name
+pub const KEYRING_SERVICE: &str = "halo_custom_field_builder";
+
+/// where the client secret was read from
+/// recorded so a run can report it without ever showing the value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource {
+    Profile(String),
+    EnvFile(PathBuf),
+    Environment,
+    File(PathBuf),
+    Command,
+    Keyring(String),
+}
+
+impl Display for SecretSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretSource::Profile(name) => write!(f, "profile '{}'", name),
+            SecretSource::EnvFile(path) => write!(f, "env file {}", path.display()),
+            SecretSource::Environment => write!(f, "environment"),
+            SecretSource::File(path) => write!(f, "secret file {}", path.display()),
+            SecretSource::Command => write!(f, "secret command"),
+            SecretSource::Keyring(entry) => write!(f, "keyring entry '{}'", entry),
+        }
+    }
+}
+
+/// a configured way to get the secret
+/// only resolved once the matching client id is known to be set
+pub enum SecretReference {
+    Value(String, SecretSource),
+    File(PathBuf),
+    Command(String),
+    Keyring(String),
+}
+
+impl SecretReference {
+    /// the secret and where it came from
+    /// errors never include the secret or the command output
+    pub fn resolve(self) -> anyhow::Result<(String, SecretSource)> {
+        let (secret, source) = match self {
+            SecretReference::Value(secret, source) => (secret, source),
+            SecretReference::File(path) => {
+                let secret = fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read secret file {}", path.display()))?;
+                (secret, SecretSource::File(path))
+            }
+            SecretReference::Command(command) => (run_command(&command)?, SecretSource::Command),
+            SecretReference::Keyring(entry) => {
+                (read_keyring(&entry)?, SecretSource::Keyring(entry))
+            }
+        };
+
+        // secret files and command output usually end with a newline
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+        if secret.is_empty() {
+            anyhow::bail!("client secret from {} is empty", source);
+        }
+
+        Ok((secret, source))
+    }
+}
+
+fn run_command(command: &str) -> anyhow::Result<String> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let output = Command::new(shell)
+        .args([flag, command])
+        .output()
+        .context("failed to run secret command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "secret command failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    String::from_utf8(output.stdout).context("secret command printed invalid utf-8")
+}
+
+#[cfg(feature = "keyring")]
+fn read_keyring(entry: &str) -> anyhow::Result<String> {
+    keyring::Entry::new(KEYRING_SERVICE, entry)
+        .and_then(|keyring_entry| keyring_entry.get_password())
+        .with_context(|| {
+            format!(
+                "failed to read keyring entry '{}' for service '{}'",
+                entry, KEYRING_SERVICE
+            )
+        })
+}
+
+#[cfg(not(feature = "keyring"))]
+fn read_keyring(entry: &str) -> anyhow::Result<String> {
+    anyhow::bail!(
+        "keyring entry '{}' cannot be read: this build does not include the keyring feature",
+        entry
+    )
+}
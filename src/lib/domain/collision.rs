@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use crate::domain::diff;
+use crate::domain::models::custom_field::CustomField;
+use crate::domain::models::existing_field::ExistingField;
+
+/// how a source field relates to the fields already on the instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldStatus {
+    New,
+    Identical { id: i64 },
+    Conflicting { id: i64 },
+}
+
+impl FieldStatus {
+    pub fn of(field: &CustomField, existing_fields: &[ExistingField]) -> Self {
+        match existing_fields
+            .iter()
+            .find(|existing| existing.matches(field))
+        {
+            None => FieldStatus::New,
+            // the same comparison as `plan`, so both agree on what is identical
+            Some(existing)
+                if existing.field.as_ref().is_some_and(|current| {
+                    diff::diff(current, field).is_ok_and(|changes| changes.is_empty())
+                }) =>
+            {
+                FieldStatus::Identical { id: existing.id }
+            }
+            Some(existing) => FieldStatus::Conflicting { id: existing.id },
+        }
+    }
+}
+
+impl Display for FieldStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldStatus::New => write!(f, "new"),
+            FieldStatus::Identical { id } => write!(f, "identical to existing field {}", id),
+            FieldStatus::Conflicting { id } => {
+                write!(f, "name already used by a different field {}", id)
+            }
+        }
+    }
+}
+
+/// what to do with source fields whose name already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// skip every field whose name exists
+    #[default]
+    Skip,
+    /// skip identical fields and record conflicting ones as failures
+    Flag,
+    /// update conflicting fields to match the source file
+    Update,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_support::{existing, single_select};
+
+    #[test]
+    fn reordered_options_are_identical() {
+        let existing_fields = [existing(11, single_select("size", 0, &["small", "medium"]))];
+        let field = single_select("size", 0, &["medium", "small"]);
+
+        assert_eq!(
+            FieldStatus::of(&field, &existing_fields),
+            FieldStatus::Identical { id: 11 }
+        );
+    }
+
+    #[test]
+    fn options_are_compared_as_halo_stores_them() {
+        let existing_fields = [existing(11, single_select("owner", 0, &["Smith John"]))];
+        let field = single_select("owner", 0, &["Smith, John"]);
+
+        assert_eq!(
+            FieldStatus::of(&field, &existing_fields),
+            FieldStatus::Identical { id: 11 }
+        );
+    }
+
+    #[test]
+    fn a_different_label_conflicts() {
+        let existing_fields = [existing(11, single_select("size", 0, &["small"]))];
+        let field = single_select("pizza size", 0, &["small"]);
+
+        assert_eq!(
+            FieldStatus::of(&field, &existing_fields),
+            FieldStatus::Conflicting { id: 11 }
+        );
+    }
+}
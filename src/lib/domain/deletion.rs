@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use crate::domain::models::custom_field::{CustomField, label::Label, usage::FieldUsage};
+use crate::domain::models::existing_field::ExistingField;
+
+/// put in front of the label of an archived field
+pub const ARCHIVED_LABEL_PREFIX: &str = "[archived] ";
+
+/// how listed fields are removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Removal {
+    /// deleted from halo along with any data they hold
+    #[default]
+    Delete,
+    /// kept with their data and relabelled so they stand out as retired
+    Archive,
+}
+
+/// a field listed for removal
+/// names are only unique within an entity, so both are needed to find it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub usage: FieldUsage,
+    pub name: String,
+}
+
+impl ManifestEntry {
+    /// the entity and lowercased name, as halo compares names case insensitively
+    pub fn key(&self) -> (FieldUsage, String) {
+        (self.usage, self.name.to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DeletionTarget {
+    Delete(ExistingField),
+    /// `field` is the existing field with its archived label
+    Archive {
+        existing: ExistingField,
+        field: CustomField,
+    },
+    /// listed for removal but not on the instance
+    NotFound(ManifestEntry),
+    /// on the instance but protected by the safety check
+    Refused {
+        existing: ExistingField,
+        reason: String,
+    },
+}
+
+/// every field listed for removal resolved against the instance
+#[derive(Debug, Clone, Default)]
+pub struct DeletionPlan {
+    pub removal: Removal,
+    pub targets: Vec<DeletionTarget>,
+}
+
+impl DeletionPlan {
+    /// `created` holds the entity and lowercased name of every field this tool created
+    /// when given, any other field is refused
+    pub fn new(
+        entries: &[ManifestEntry],
+        existing_fields: &[ExistingField],
+        created: Option<&HashSet<(FieldUsage, String)>>,
+        removal: Removal,
+    ) -> Self {
+        let targets = entries
+            .iter()
+            .map(|entry| {
+                let Some(existing) = existing_fields.iter().find(|existing| {
+                    existing.usage == entry.usage && existing.has_name(&entry.name)
+                }) else {
+                    return DeletionTarget::NotFound(entry.clone());
+                };
+
+                match created {
+                    Some(created) if !created.contains(&entry.key()) => DeletionTarget::Refused {
+                        existing: existing.clone(),
+                        reason: "not created by this tool".to_string(),
+                    },
+                    _ if removal == Removal::Archive => Self::archive(existing),
+                    _ => DeletionTarget::Delete(existing.clone()),
+                }
+            })
+            .collect();
+
+        Self { removal, targets }
+    }
+
+    /// archiving sends the whole definition back, so the field has to be modeled
+    fn archive(existing: &ExistingField) -> DeletionTarget {
+        let refuse = |reason: &str| DeletionTarget::Refused {
+            existing: existing.clone(),
+            reason: reason.to_string(),
+        };
+
+        let Some(current) = &existing.field else {
+            return refuse("uses a definition this tool cannot model, so it cannot be archived");
+        };
+        let label = current.label.to_string();
+        if label.starts_with(ARCHIVED_LABEL_PREFIX) {
+            return refuse("already archived");
+        }
+        match Label::new(&format!("{}{}", ARCHIVED_LABEL_PREFIX, label)) {
+            Ok(label) => DeletionTarget::Archive {
+                existing: existing.clone(),
+                field: CustomField {
+                    label,
+                    ..current.clone()
+                },
+            },
+            Err(e) => refuse(&format!("archived label {}", e)),
+        }
+    }
+
+    /// fields that will be deleted or archived
+    pub fn deletions(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|target| {
+                matches!(
+                    target,
+                    DeletionTarget::Delete(_) | DeletionTarget::Archive { .. }
+                )
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_support::{existing, field, unmodeled};
+
+    fn entry(usage: FieldUsage, name: &str) -> ManifestEntry {
+        ManifestEntry {
+            usage,
+            name: name.to_string(),
+        }
+    }
+
+    fn created(fields: &[(FieldUsage, &str)]) -> HashSet<(FieldUsage, String)> {
+        fields
+            .iter()
+            .map(|(usage, name)| (*usage, name.to_lowercase()))
+            .collect()
+    }
+
+    #[test]
+    fn only_targets_the_listed_entity() {
+        let existing_fields = [
+            unmodeled(1, FieldUsage::Tickets, "priority"),
+            unmodeled(2, FieldUsage::Clients, "priority"),
+        ];
+        let plan = DeletionPlan::new(
+            &[entry(FieldUsage::Tickets, "Priority")],
+            &existing_fields,
+            None,
+            Removal::Delete,
+        );
+
+        assert_eq!(plan.deletions(), 1);
+        assert!(matches!(&plan.targets[0], DeletionTarget::Delete(field) if field.id == 1));
+    }
+
+    #[test]
+    fn refuses_a_name_created_for_another_entity() {
+        let existing_fields = [
+            unmodeled(1, FieldUsage::Tickets, "priority"),
+            unmodeled(2, FieldUsage::Clients, "priority"),
+        ];
+        let created = created(&[(FieldUsage::Tickets, "priority")]);
+        let plan = DeletionPlan::new(
+            &[entry(FieldUsage::Clients, "priority")],
+            &existing_fields,
+            Some(&created),
+            Removal::Delete,
+        );
+
+        assert_eq!(plan.deletions(), 0);
+        assert!(matches!(
+            &plan.targets[0],
+            DeletionTarget::Refused { existing, .. } if existing.id == 2
+        ));
+    }
+
+    #[test]
+    fn reports_an_entity_without_the_name_as_missing() {
+        let existing_fields = [unmodeled(1, FieldUsage::Tickets, "priority")];
+        let plan = DeletionPlan::new(
+            &[entry(FieldUsage::Users, "priority")],
+            &existing_fields,
+            None,
+            Removal::Delete,
+        );
+
+        assert!(matches!(&plan.targets[0], DeletionTarget::NotFound(_)));
+    }
+
+    #[test]
+    fn archiving_relabels_a_modeled_field() {
+        let existing_fields = [
+            existing(1, field("priority", 1, "tickets")),
+            unmodeled(2, FieldUsage::Tickets, "unmodeled"),
+        ];
+        let plan = DeletionPlan::new(
+            &[
+                entry(FieldUsage::Tickets, "priority"),
+                entry(FieldUsage::Tickets, "unmodeled"),
+            ],
+            &existing_fields,
+            None,
+            Removal::Archive,
+        );
+
+        assert_eq!(plan.deletions(), 1);
+        assert!(matches!(
+            &plan.targets[0],
+            DeletionTarget::Archive { field, .. } if field.label.to_string() == "[archived] priority"
+        ));
+        assert!(matches!(&plan.targets[1], DeletionTarget::Refused { .. }));
+    }
+}
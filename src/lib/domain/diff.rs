@@ -0,0 +1,248 @@
+use crate::domain::models::custom_field::{CustomField, label::Label};
+
+/// a single difference between a field on the instance
+/// and the same field in the source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldChange {
+    Label {
+        from: Label,
+        to: Label,
+    },
+    InputType {
+        from: Option<u8>,
+        to: Option<u8>,
+    },
+    OptionsAdded(Vec<String>),
+    OptionsRemoved(Vec<String>),
+    Setting {
+        name: &'static str,
+        from: bool,
+        to: bool,
+    },
+}
+
+impl FieldChange {
+    /// this tool only ever appends selection values and sends settings on create
+    /// so removed options and changed settings are reported but left in place
+    pub fn is_applied(&self) -> bool {
+        !matches!(
+            self,
+            FieldChange::OptionsRemoved(_) | FieldChange::Setting { .. }
+        )
+    }
+}
+
+/// every option the changes add to a field
+pub fn added_options(changes: &[FieldChange]) -> Vec<String> {
+    changes
+        .iter()
+        .filter_map(|change| match change {
+            FieldChange::OptionsAdded(options) => Some(options.clone()),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// halo cannot change the type of an existing field
+/// so a type change is reported instead of diffed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldTypeMismatch {
+    pub from: u8,
+    pub to: u8,
+}
+
+/// every change needed to turn `current` into `desired`
+pub fn diff(
+    current: &CustomField,
+    desired: &CustomField,
+) -> Result<Vec<FieldChange>, FieldTypeMismatch> {
+    let current_type_id = current.field_type.field_type_id();
+    let desired_type_id = desired.field_type.field_type_id();
+    if current_type_id != desired_type_id {
+        return Err(FieldTypeMismatch {
+            from: current_type_id,
+            to: desired_type_id,
+        });
+    }
+
+    let mut changes = Vec::new();
+
+    if current.label != desired.label {
+        changes.push(FieldChange::Label {
+            from: current.label.clone(),
+            to: desired.label.clone(),
+        });
+    }
+
+    let current_input_type_id = current.field_type.input_type_id();
+    let desired_input_type_id = desired.field_type.input_type_id();
+    if current_input_type_id != desired_input_type_id {
+        changes.push(FieldChange::InputType {
+            from: current_input_type_id,
+            to: desired_input_type_id,
+        });
+    }
+
+    let current_options = current.field_type.selection_options().unwrap_or_default();
+    // compared as halo will store them, so options it cannot keep as written are not re-added
+    let desired_options: Vec<String> = desired
+        .field_type
+        .selection_options()
+        .unwrap_or_default()
+        .iter()
+        .map(|option| desired.field_type.stored_option(option))
+        .collect();
+
+    let added: Vec<String> = desired_options
+        .iter()
+        .filter(|option| !current_options.contains(option))
+        .cloned()
+        .collect();
+    if !added.is_empty() {
+        changes.push(FieldChange::OptionsAdded(added));
+    }
+
+    let removed: Vec<String> = current_options
+        .iter()
+        .filter(|option| !desired_options.contains(option))
+        .cloned()
+        .collect();
+    if !removed.is_empty() {
+        changes.push(FieldChange::OptionsRemoved(removed));
+    }
+
+    // usage is not compared, fields are only matched within the same entity
+    let (current_settings, desired_settings) = (&current.settings, &desired.settings);
+    for (name, from, to) in [
+        (
+            "searchable",
+            current_settings.searchable,
+            desired_settings.searchable,
+        ),
+        (
+            "user_searchable",
+            current_settings.user_searchable,
+            desired_settings.user_searchable,
+        ),
+        (
+            "calendar_searchable",
+            current_settings.calendar_searchable,
+            desired_settings.calendar_searchable,
+        ),
+        (
+            "copytochild",
+            current_settings.copy_to_child,
+            desired_settings.copy_to_child,
+        ),
+        (
+            "copytochildonupdate",
+            current_settings.copy_to_child_on_update,
+            desired_settings.copy_to_child_on_update,
+        ),
+    ] {
+        if from != to {
+            changes.push(FieldChange::Setting { name, from, to });
+        }
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::custom_field::field_type::type_reference::TypeReference;
+    use crate::domain::models::custom_field::settings::RequestedSettings;
+    use crate::domain::test_support::single_select;
+
+    fn password(settings: RequestedSettings) -> CustomField {
+        CustomField::new(
+            "apiKey",
+            "api key",
+            TypeReference::Id(0),
+            Some(TypeReference::Name("password".to_string())),
+            Vec::new(),
+            settings,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn a_changed_setting_is_reported_but_not_applied() {
+        let mut current = password(RequestedSettings::default());
+        current.settings.searchable = true;
+        let desired = password(RequestedSettings {
+            searchable: Some(false),
+            ..RequestedSettings::default()
+        });
+
+        let changes = diff(&current, &desired).unwrap();
+
+        assert_eq!(
+            changes,
+            [FieldChange::Setting {
+                name: "searchable",
+                from: true,
+                to: false
+            }]
+        );
+        assert!(!changes[0].is_applied());
+    }
+
+    #[test]
+    fn matching_settings_are_unchanged() {
+        let current = password(RequestedSettings::default());
+        let desired = password(RequestedSettings {
+            copy_to_child: Some(true),
+            ..RequestedSettings::default()
+        });
+
+        assert_eq!(diff(&current, &desired), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn identical_fields_have_no_changes() {
+        let field = single_select("size", 0, &["small", "medium"]);
+
+        assert_eq!(diff(&field, &field), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn reports_label_input_type_and_option_changes() {
+        let current = single_select("size", 0, &["small", "medium"]);
+        let desired = single_select("pizza size", 2, &["medium", "large"]);
+
+        let changes = diff(&current, &desired).unwrap();
+
+        assert_eq!(
+            changes,
+            [
+                FieldChange::Label {
+                    from: current.label.clone(),
+                    to: desired.label.clone()
+                },
+                FieldChange::InputType {
+                    from: Some(0),
+                    to: Some(2)
+                },
+                FieldChange::OptionsAdded(vec!["large".to_string()]),
+                FieldChange::OptionsRemoved(vec!["small".to_string()]),
+            ]
+        );
+        assert_eq!(added_options(&changes), ["large"]);
+        assert!(changes[..3].iter().all(FieldChange::is_applied));
+        assert!(!changes[3].is_applied());
+    }
+
+    #[test]
+    fn a_type_change_is_a_mismatch() {
+        let current = single_select("size", 0, &["small"]);
+        let desired = password(RequestedSettings::default());
+
+        assert_eq!(
+            diff(&current, &desired),
+            Err(FieldTypeMismatch { from: 2, to: 0 })
+        );
+    }
+}
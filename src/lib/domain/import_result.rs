@@ -1,19 +1,40 @@
 use chrono::{DateTime, Local};
 use colored::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
 
-#[derive(Debug, Serialize)]
+use crate::domain::models::custom_field::usage::FieldUsage;
+use crate::hprintln;
+
+/// what was done, or would have been done, to a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldAction {
+    Create,
+    Update,
+    Delete,
+    Archive,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldResult {
+    pub name: String,
     pub label: String,
+    pub usage: FieldUsage,
+    pub action: FieldAction,
     pub success: bool,
     pub error: Option<String>,
+    /// requests sent for this field, more than one when halo had to be retried
+    pub attempts: u32,
     pub timestamp: DateTime<Local>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ImportResults {
     pub successful: Vec<FieldResult>,
     pub failed: Vec<FieldResult>,
+    pub skipped: Vec<FieldResult>,
 }
 
 impl ImportResults {
@@ -21,50 +42,179 @@ impl ImportResults {
         Self {
             successful: Vec::new(),
             failed: Vec::new(),
+            skipped: Vec::new(),
         }
     }
 
-    pub fn add_success(&mut self, label: String) {
+    pub fn add_success(
+        &mut self,
+        name: impl Display,
+        label: impl Display,
+        usage: FieldUsage,
+        action: FieldAction,
+        attempts: u32,
+    ) {
         self.successful.push(FieldResult {
-            label,
+            name: name.to_string(),
+            label: label.to_string(),
+            usage,
+            action,
             success: true,
             error: None,
+            attempts,
             timestamp: Local::now(),
         });
     }
 
-    pub fn add_failure(&mut self, label: String, error: String) {
+    pub fn add_failure(
+        &mut self,
+        name: impl Display,
+        label: impl Display,
+        usage: FieldUsage,
+        action: FieldAction,
+        attempts: u32,
+        error: String,
+    ) {
         self.failed.push(FieldResult {
-            label,
+            name: name.to_string(),
+            label: label.to_string(),
+            usage,
+            action,
             success: false,
             error: Some(error),
+            attempts,
             timestamp: Local::now(),
         });
     }
 
+    pub fn add_skipped(
+        &mut self,
+        name: impl Display,
+        label: impl Display,
+        usage: FieldUsage,
+        action: FieldAction,
+        reason: String,
+    ) {
+        self.skipped.push(FieldResult {
+            name: name.to_string(),
+            label: label.to_string(),
+            usage,
+            action,
+            success: false,
+            error: Some(reason),
+            attempts: 0,
+            timestamp: Local::now(),
+        });
+    }
+
+    /// the most recent result recorded for `name`
+    pub fn latest_for(&self, name: &str) -> Option<&FieldResult> {
+        self.successful
+            .iter()
+            .chain(&self.failed)
+            .chain(&self.skipped)
+            .filter(|result| result.name == name)
+            .max_by_key(|result| result.timestamp)
+    }
+
+    /// fields that needed more than one attempt, whatever the outcome
+    pub fn retried(&self) -> impl Iterator<Item = &FieldResult> {
+        self.successful
+            .iter()
+            .chain(&self.failed)
+            .filter(|result| result.attempts > 1)
+    }
+
+    /// successful, failed and skipped counts for each entity
+    pub fn by_usage(&self) -> BTreeMap<FieldUsage, (usize, usize, usize)> {
+        let mut counts: BTreeMap<FieldUsage, (usize, usize, usize)> = BTreeMap::new();
+        for result in &self.successful {
+            counts.entry(result.usage).or_default().0 += 1;
+        }
+        for result in &self.failed {
+            counts.entry(result.usage).or_default().1 += 1;
+        }
+        for result in &self.skipped {
+            counts.entry(result.usage).or_default().2 += 1;
+        }
+        counts
+    }
+
     pub fn log_summary(&self) {
-        println!("\n{}", "Import Summary:".bright_blue().bold());
-        println!("{}", "=".repeat(80).bright_blue());
+        hprintln!("\n{}", "Import Summary:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
 
-        println!(
+        hprintln!(
             "• Total fields processed: {}",
-            (self.successful.len() + self.failed.len())
+            (self.successful.len() + self.failed.len() + self.skipped.len())
                 .to_string()
                 .bright_yellow()
         );
-        println!(
+        hprintln!(
             "• Successful imports: {}",
             self.successful.len().to_string().bright_green()
         );
-        println!(
+        hprintln!(
             "• Failed imports: {}",
             self.failed.len().to_string().bright_red()
         );
+        hprintln!(
+            "• Skipped fields: {}",
+            self.skipped.len().to_string().bright_cyan()
+        );
+
+        let retried: Vec<&FieldResult> = self.retried().collect();
+        hprintln!(
+            "• Retried fields: {}",
+            retried.len().to_string().bright_magenta()
+        );
+
+        let by_usage = self.by_usage();
+        if !by_usage.is_empty() {
+            hprintln!("\n{}", "Per Entity:".bright_blue().bold());
+            for (usage, (successful, failed, skipped)) in &by_usage {
+                hprintln!(
+                    "• {}: {} successful, {} failed, {} skipped",
+                    usage.to_string().bright_yellow(),
+                    successful.to_string().bright_green(),
+                    failed.to_string().bright_red(),
+                    skipped.to_string().bright_cyan()
+                );
+            }
+        }
+
+        if !retried.is_empty() {
+            hprintln!("\n{}", "Retried Fields:".bright_magenta().bold());
+            for result in &retried {
+                let outcome = if result.success {
+                    "succeeded".bright_green()
+                } else {
+                    "failed".bright_red()
+                };
+                hprintln!(
+                    "• {} ({} attempts, {})",
+                    result.label.bright_yellow(),
+                    result.attempts.to_string().bright_magenta(),
+                    outcome
+                );
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            hprintln!("\n{}", "Skipped Fields:".bright_cyan().bold());
+            for result in &self.skipped {
+                hprintln!(
+                    "• {} ({})",
+                    result.label.bright_yellow(),
+                    result.error.as_ref().unwrap().bright_cyan()
+                );
+            }
+        }
 
         if !self.failed.is_empty() {
-            println!("\n{}", "Failed Fields:".bright_red().bold());
+            hprintln!("\n{}", "Failed Fields:".bright_red().bold());
             for result in &self.failed {
-                println!(
+                hprintln!(
                     "• {} ({})",
                     result.label.bright_yellow(),
                     result.error.as_ref().unwrap().bright_red()
@@ -72,6 +222,6 @@ impl ImportResults {
             }
         }
 
-        println!("{}", "=".repeat(80).bright_blue());
+        hprintln!("{}", "=".repeat(80).bright_blue());
     }
 }
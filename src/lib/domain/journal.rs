@@ -0,0 +1,212 @@
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::domain::import_result::{FieldAction, FieldResult};
+use crate::domain::logging::LOGS_DIR;
+use crate::domain::models::custom_field::{CustomField, usage::FieldUsage};
+
+const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+
+/// the entity and lowercased name of a created field
+type CreatedField = (FieldUsage, String);
+
+/// one line of the journal
+/// `source_hash` ties the entry to the exact source file it came from
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    source_hash: String,
+    #[serde(flatten)]
+    event: JournalEvent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum JournalEvent {
+    Attempted {
+        name: String,
+        label: String,
+        timestamp: DateTime<Local>,
+    },
+    Finished(FieldResult),
+}
+
+/// append-only record of every field attempt and its outcome
+/// written before and after each request so an interrupted import can be resumed
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+    source_hash: String,
+    resume: bool,
+    created: HashSet<CreatedField>,
+    unconfirmed: Vec<String>,
+}
+
+impl Journal {
+    /// opens the journal for `source_file`
+    /// with `resume` set, fields an earlier run confirmed as created are skipped
+    pub fn open(source_file: &str, resume: bool) -> anyhow::Result<Self> {
+        let source_hash = hash_file(Path::new(source_file))?;
+        let path = Path::new(LOGS_DIR).join(JOURNAL_FILE_NAME);
+
+        let (created, unconfirmed) = if path.exists() {
+            Self::replay(&path, &source_hash)?
+        } else {
+            (HashSet::new(), Vec::new())
+        };
+
+        fs::create_dir_all(LOGS_DIR).context("failed to create logs directory")?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open journal {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            source_hash,
+            resume,
+            created,
+            unconfirmed,
+        })
+    }
+
+    /// fields confirmed as created for this source file
+    /// and names that were attempted without a recorded outcome
+    fn replay(
+        path: &Path,
+        source_hash: &str,
+    ) -> anyhow::Result<(HashSet<CreatedField>, Vec<String>)> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to read journal {}", path.display()))?;
+
+        let mut created = HashSet::new();
+        let mut pending: Vec<String> = Vec::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.context("failed to read journal")?;
+            // a run killed mid-write can leave a partial last line
+            let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) else {
+                continue;
+            };
+            if entry.source_hash != source_hash {
+                continue;
+            }
+
+            match entry.event {
+                JournalEvent::Attempted { name, .. } => {
+                    if !pending.contains(&name) {
+                        pending.push(name);
+                    }
+                }
+                JournalEvent::Finished(result) => {
+                    pending.retain(|name| name != &result.name);
+                    if result.success && result.action == FieldAction::Create {
+                        created.insert((result.usage, result.name.to_lowercase()));
+                    }
+                }
+            }
+        }
+
+        Ok((created, pending))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn source_hash(&self) -> &str {
+        &self.source_hash
+    }
+
+    pub fn is_resuming(&self) -> bool {
+        self.resume
+    }
+
+    /// fields whose request was sent by an earlier run without a recorded outcome
+    /// halo may or may not have created them
+    pub fn unconfirmed(&self) -> &[String] {
+        &self.unconfirmed
+    }
+
+    /// true when resuming and an earlier run confirmed `field` as created
+    pub fn already_created(&self, field: &CustomField) -> bool {
+        self.resume
+            && self
+                .created
+                .contains(&(field.settings.usage, field.name.to_string().to_lowercase()))
+    }
+
+    pub fn record_attempt(&self, field: &CustomField) -> anyhow::Result<()> {
+        self.append(JournalEvent::Attempted {
+            name: field.name.to_string(),
+            label: field.label.to_string(),
+            timestamp: Local::now(),
+        })
+    }
+
+    pub fn record_outcome(&self, result: &FieldResult) -> anyhow::Result<()> {
+        self.append(JournalEvent::Finished(result.clone()))
+    }
+
+    fn append(&self, event: JournalEvent) -> anyhow::Result<()> {
+        let entry = JournalEntry {
+            source_hash: self.source_hash.clone(),
+            event,
+        };
+        let line = serde_json::to_string(&entry).context("failed to serialize journal entry")?;
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("journal lock poisoned"))?;
+        writeln!(file, "{}", line).context("failed to write journal")?;
+        // flushed per entry so nothing is lost when the process dies
+        file.sync_data().context("failed to flush journal")?;
+
+        Ok(())
+    }
+}
+
+/// every recorded outcome regardless of source file
+/// so fields created by an interrupted run still count as created by this tool
+pub(crate) fn finished_results() -> anyhow::Result<Vec<FieldResult>> {
+    let path = Path::new(LOGS_DIR).join(JOURNAL_FILE_NAME);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file =
+        File::open(&path).with_context(|| format!("failed to read journal {}", path.display()))?;
+
+    let mut results = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.context("failed to read journal")?;
+        if let Ok(JournalEntry {
+            event: JournalEvent::Finished(result),
+            ..
+        }) = serde_json::from_str(&line)
+        {
+            results.push(result);
+        }
+    }
+
+    Ok(results)
+}
+
+/// sha-256 of the source file contents as lowercase hex
+fn hash_file(path: &Path) -> anyhow::Result<String> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read source file {}", path.display()))?;
+    let digest = Sha256::digest(&bytes);
+
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
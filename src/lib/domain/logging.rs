@@ -2,17 +2,50 @@ use anyhow::Context;
 use chrono::{DateTime, Duration, Local};
 use log::{error, info, LevelFilter};
 use simplelog::*;
+use std::collections::HashSet;
 use std::fs;
 use std::fs::OpenOptions;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::domain::import_result::ImportResults;
+use crate::domain::import_result::{FieldAction, FieldResult, ImportResults};
+use crate::domain::journal;
+use crate::domain::models::custom_field::usage::FieldUsage;
 
+pub(crate) const LOGS_DIR: &str = "logs";
+const RESULTS_FILE_PREFIX: &str = "results_";
 const MAX_LOG_AGE: i64 = 7;
 const MAX_LOG_COUNT: usize = 100;
 
-pub fn setup_logging() -> anyhow::Result<()> {
-    let logs_dir = Path::new("logs");
+/// set when stdout carries program output such as dry run json
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// like `println!`, but to stderr while stdout carries program output
+#[macro_export]
+macro_rules! hprintln {
+    () => {
+        $crate::domain::logging::print_human(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::domain::logging::print_human(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// like `print!`, but to stderr while stdout carries program output
+#[macro_export]
+macro_rules! hprint {
+    ($($arg:tt)*) => {
+        $crate::domain::logging::print_human(format_args!($($arg)*))
+    };
+}
+
+/// `log_to_stderr` keeps stdout free for program output
+/// screens and summaries follow the log to stderr
+pub fn setup_logging(log_to_stderr: bool) -> anyhow::Result<()> {
+    STDOUT_RESERVED.store(log_to_stderr, Ordering::Relaxed);
+
+    let logs_dir = Path::new(LOGS_DIR);
     fs::create_dir_all(logs_dir).context("failed to create logs directory")?;
 
     cleanup_old_logs(logs_dir)?;
@@ -35,11 +68,17 @@ pub fn setup_logging() -> anyhow::Result<()> {
         .set_time_level(LevelFilter::Off)
         .build();
 
+    let terminal_mode = if log_to_stderr {
+        TerminalMode::Stderr
+    } else {
+        TerminalMode::Mixed
+    };
+
     CombinedLogger::init(vec![
         TermLogger::new(
             LevelFilter::Info,
             term_config,
-            TerminalMode::Mixed,
+            terminal_mode,
             ColorChoice::Auto,
         ),
         WriteLogger::new(
@@ -47,7 +86,6 @@ pub fn setup_logging() -> anyhow::Result<()> {
             file_config,
             OpenOptions::new()
                 .create(true)
-                .write(true)
                 .append(true)
                 .open(&log_file)
                 .context("failed to open log file")?,
@@ -62,11 +100,29 @@ pub fn setup_logging() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// status blocks, summaries and prompts meant for a person rather than a program
+pub fn print_human(args: std::fmt::Arguments) {
+    if STDOUT_RESERVED.load(Ordering::Relaxed) {
+        eprint!("{}", args);
+    } else {
+        print!("{}", args);
+    }
+}
+
+/// flushes a prompt written without a newline
+pub fn flush_human() -> io::Result<()> {
+    if STDOUT_RESERVED.load(Ordering::Relaxed) {
+        io::stderr().flush()
+    } else {
+        io::stdout().flush()
+    }
+}
+
 fn cleanup_old_logs(logs_dir: &Path) -> anyhow::Result<()> {
     let mut log_files: Vec<_> = fs::read_dir(logs_dir)
         .context("failed to read logs directory")?
         .filter_map(|r| r.ok())
-        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "log"))
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
         .collect();
 
     log_files.sort_by(|a, b| {
@@ -78,12 +134,12 @@ fn cleanup_old_logs(logs_dir: &Path) -> anyhow::Result<()> {
     let cutoff_date = Local::now() - Duration::days(MAX_LOG_AGE);
 
     for entry in log_files.iter().skip(MAX_LOG_COUNT) {
-        if let Ok(metadata) = entry.metadata() {
-            if let Ok(modified) = metadata.modified() {
-                let modified: DateTime<Local> = modified.into();
-                if modified < cutoff_date {
-                    let _ = fs::remove_file(entry.path());
-                }
+        if let Ok(metadata) = entry.metadata()
+            && let Ok(modified) = metadata.modified()
+        {
+            let modified: DateTime<Local> = modified.into();
+            if modified < cutoff_date {
+                let _ = fs::remove_file(entry.path());
             }
         }
     }
@@ -91,6 +147,71 @@ fn cleanup_old_logs(logs_dir: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// keeps a json record of every run beside its log
+/// results files are not rotated since they record what this tool created
+pub fn save_results(results: &ImportResults) -> anyhow::Result<PathBuf> {
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let results_file =
+        Path::new(LOGS_DIR).join(format!("{}{}.json", RESULTS_FILE_PREFIX, timestamp));
+
+    let json = serde_json::to_string_pretty(results).context("failed to serialize results")?;
+    fs::write(&results_file, json).context("failed to write results file")?;
+
+    Ok(results_file)
+}
+
+pub fn load_results(path: &Path) -> anyhow::Result<ImportResults> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("failed to read results file {}", path.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("failed to parse results file {}", path.display()))
+}
+
+/// the entity and lowercased name of every field this tool created and has not deleted since
+/// replayed from every results file and the import journal in the logs directory
+pub fn created_fields() -> anyhow::Result<HashSet<(FieldUsage, String)>> {
+    let mut outcomes: Vec<FieldResult> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(LOGS_DIR) {
+        for entry in entries.filter_map(|r| r.ok()) {
+            let path = entry.path();
+            let is_results_file =
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(RESULTS_FILE_PREFIX) && name.ends_with(".json")
+                    });
+
+            if is_results_file {
+                outcomes.extend(load_results(&path)?.successful);
+            }
+        }
+    }
+
+    outcomes.extend(
+        journal::finished_results()?
+            .into_iter()
+            .filter(|result| result.success),
+    );
+    outcomes.sort_by_key(|result| result.timestamp);
+
+    let mut created = HashSet::new();
+    for result in outcomes {
+        let key = (result.usage, result.name.to_lowercase());
+        match result.action {
+            FieldAction::Create => {
+                created.insert(key);
+            }
+            FieldAction::Delete => {
+                created.remove(&key);
+            }
+            FieldAction::Update | FieldAction::Archive => {}
+        }
+    }
+
+    Ok(created)
+}
+
 #[allow(dead_code)]
 fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
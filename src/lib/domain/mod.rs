@@ -1,3 +1,11 @@
+pub mod collision;
+pub mod deletion;
+pub mod diff;
 pub mod import_result;
+pub mod journal;
 pub mod logging;
 pub mod models;
+pub mod plan;
+#[cfg(test)]
+pub mod test_support;
+pub mod validation_report;
@@ -1,11 +1,16 @@
 pub mod field_type;
 pub mod label;
 pub mod name;
+pub mod selection_option;
+pub mod settings;
+pub mod usage;
 
 use crate::domain::models::custom_field::{
-    field_type::{FieldType, InvalidFieldType},
+    field_type::{FieldType, InvalidFieldType, type_reference::TypeReference},
     label::{InvalidLabel, Label},
     name::{InvalidName, Name},
+    settings::{FieldSettings, InvalidFieldSettings, RequestedSettings},
+    usage::FieldUsage,
 };
 use thiserror::Error;
 
@@ -17,6 +22,8 @@ pub enum InvalidCustomField {
     Label(InvalidLabel),
     #[error(transparent)]
     FieldType(InvalidFieldType),
+    #[error(transparent)]
+    Settings(InvalidFieldSettings),
 }
 
 impl From<InvalidName> for InvalidCustomField {
@@ -37,32 +44,125 @@ impl From<InvalidFieldType> for InvalidCustomField {
     }
 }
 
-#[derive(Debug, Clone)]
+impl From<InvalidFieldSettings> for InvalidCustomField {
+    fn from(value: InvalidFieldSettings) -> Self {
+        InvalidCustomField::Settings(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CustomField {
     pub name: Name,
     pub label: Label,
     pub field_type: FieldType,
+    pub settings: FieldSettings,
 }
 impl CustomField {
+    /// the first problem only, see `validate` for all of them
     pub fn new(
         name: &str,
         label: &str,
-        field_type_id: u8,
-        input_type_id: Option<u8>,
-        selection_options: Option<String>,
+        field_type: TypeReference,
+        input_type: Option<TypeReference>,
+        selection_options: Vec<String>,
+        settings: RequestedSettings,
     ) -> Result<Self, InvalidCustomField> {
-        let name = Name::new(name)?;
-        let label = Label::new(label)?;
-        let selection_options = match selection_options {
-            Some(options) => options.split(",").map(String::from).collect(),
-            None => vec![],
-        };
-        let field_type = FieldType::new(field_type_id, input_type_id, selection_options)?;
-
-        Ok(Self {
+        Self::validate(
             name,
             label,
             field_type,
-        })
+            input_type,
+            selection_options,
+            settings,
+        )
+        .map_err(|mut problems| problems.remove(0))
+    }
+
+    /// checks every part on its own, so one row reports all of its problems at once
+    /// settings depend on the type, so only the usage is checked while the type is invalid
+    pub fn validate(
+        name: &str,
+        label: &str,
+        field_type: TypeReference,
+        input_type: Option<TypeReference>,
+        selection_options: Vec<String>,
+        settings: RequestedSettings,
+    ) -> Result<Self, Vec<InvalidCustomField>> {
+        let name = Name::new(name).map_err(InvalidCustomField::from);
+        let label = Label::new(label).map_err(InvalidCustomField::from);
+        let field_type =
+            FieldType::from_references(&field_type, input_type.as_ref(), selection_options)
+                .map_err(InvalidCustomField::from);
+        let settings = match &field_type {
+            Ok(field_type) => FieldSettings::new(settings, field_type).map(Some),
+            Err(_) => settings
+                .usage
+                .as_ref()
+                .map(FieldUsage::from_reference)
+                .transpose()
+                .map(|_| None),
+        }
+        .map_err(InvalidCustomField::from);
+
+        match (name, label, field_type, settings) {
+            (Ok(name), Ok(label), Ok(field_type), Ok(Some(settings))) => Ok(Self {
+                name,
+                label,
+                field_type,
+                settings,
+            }),
+            (name, label, field_type, settings) => {
+                Err([name.err(), label.err(), field_type.err(), settings.err()]
+                    .into_iter()
+                    .flatten()
+                    .collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_reports_every_invalid_part() {
+        let problems = CustomField::validate(
+            "bad name!",
+            " ",
+            TypeReference::Name("nonsense".to_string()),
+            None,
+            Vec::new(),
+            RequestedSettings {
+                usage: Some(TypeReference::Id(0)),
+                ..RequestedSettings::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            problems.as_slice(),
+            [
+                InvalidCustomField::Name(_),
+                InvalidCustomField::Label(InvalidLabel::Empty),
+                InvalidCustomField::FieldType(InvalidFieldType::UnknownFieldType(_)),
+                InvalidCustomField::Settings(InvalidFieldSettings::Usage),
+            ]
+        ));
+    }
+
+    #[test]
+    fn new_keeps_the_first_problem() {
+        assert!(matches!(
+            CustomField::new(
+                "ok",
+                "",
+                TypeReference::Id(99),
+                None,
+                Vec::new(),
+                RequestedSettings::default(),
+            ),
+            Err(InvalidCustomField::Label(InvalidLabel::Empty))
+        ));
     }
 }
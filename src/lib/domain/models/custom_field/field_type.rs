@@ -1,10 +1,16 @@
 pub mod input_types;
+pub mod type_reference;
 use crate::domain::models::custom_field::field_type::input_types::{
     date_input_type::{DateInputType, InvalidDateInputType},
     single_select_input_type::{InvalidSingleSelectInputType, SingleSelectInputType},
     text_input_type::{InvalidTextInputType, TextInputType},
 };
+use crate::domain::models::custom_field::field_type::type_reference::{
+    TypeReference, UnknownName, find_by_name,
+};
+use crate::domain::models::custom_field::selection_option::SelectionOptions;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -13,6 +19,31 @@ pub enum InvalidFieldType {
     InvalidFieldTypeId,
     #[error(transparent)]
     InvalidInputType(anyhow::Error),
+    #[error("unknown field type {0}")]
+    UnknownFieldType(UnknownName),
+    #[error("unknown {field_type} input type {name}")]
+    UnknownInputType {
+        field_type: &'static str,
+        name: UnknownName,
+    },
+    #[error("selection fields need at least one option")]
+    NoOptions,
+    #[error("option {position} is empty")]
+    EmptyOption { position: usize },
+    #[error("option {position} is longer than 256 characters")]
+    OptionTooLong { position: usize },
+    #[error("option '{option}' repeats '{first}', options must differ by more than case")]
+    DuplicateOption { option: String, first: String },
+    #[error("options '{first}' and '{option}' would both be stored by halo as '{stored}'")]
+    DuplicateStoredOption {
+        option: String,
+        first: String,
+        stored: String,
+    },
+    #[error(
+        "nested options such as 'parent > child' need a single select with the tree input type"
+    )]
+    NestedOptions,
 }
 
 impl From<InvalidTextInputType> for InvalidFieldType {
@@ -41,10 +72,10 @@ pub enum FieldType {
     Memo,
     SingleSelect {
         input_type: SingleSelectInputType,
-        selection_options: Vec<String>,
+        selection_options: SelectionOptions,
     },
     MultiSelect {
-        selection_options: Vec<String>,
+        selection_options: SelectionOptions,
     },
     Date {
         input_type: DateInputType,
@@ -54,10 +85,112 @@ pub enum FieldType {
     Rich,
 }
 impl FieldType {
+    /// field type names accepted in source files, the canonical name first
+    pub const NAMES: &'static [(u8, &'static [&'static str])] = &[
+        (0, &["text", "string"]),
+        (1, &["memo", "multiline", "textarea"]),
+        (
+            2,
+            &["single_select", "select", "dropdown", "single_selection"],
+        ),
+        (
+            3,
+            &[
+                "multi_select",
+                "multiselect",
+                "multiple_select",
+                "multiple_selection",
+            ],
+        ),
+        (4, &["date"]),
+        (5, &["time"]),
+        (6, &["checkbox", "boolean", "bool"]),
+        (10, &["rich", "rich_text", "html"]),
+    ];
+
+    /// builds a field type from ids or names as written in a source file
+    /// input type names are looked up among the input types of the field type
+    /// and options are checked, which fields already in halo are not held to
+    pub fn from_references(
+        field_type: &TypeReference,
+        input_type: Option<&TypeReference>,
+        selection_options: Vec<String>,
+    ) -> Result<Self, InvalidFieldType> {
+        let field_type_id = match field_type {
+            TypeReference::Id(id) => *id,
+            TypeReference::Name(name) => {
+                find_by_name(name, Self::NAMES).map_err(InvalidFieldType::UnknownFieldType)?
+            }
+        };
+        let input_type_id = match input_type {
+            None => None,
+            Some(TypeReference::Id(id)) => Some(*id),
+            Some(TypeReference::Name(name)) => Self::input_type_id_by_name(field_type_id, name)?,
+        };
+        let selection_options = SelectionOptions::new(selection_options)?;
+
+        let field_type = Self::new(field_type_id, input_type_id, selection_options)?;
+        if field_type
+            .selection_options()
+            .is_some_and(|options| options.is_empty())
+        {
+            return Err(InvalidFieldType::NoOptions);
+        }
+        field_type.check_stored_duplicates()?;
+
+        Ok(field_type)
+    }
+
+    /// options that only differ by the commas halo drops would be stored twice
+    /// compared ignoring case, like the options as written
+    fn check_stored_duplicates(&self) -> Result<(), InvalidFieldType> {
+        if self.option_tree().is_some() {
+            return Ok(());
+        }
+
+        let mut seen: HashMap<String, String> = HashMap::new();
+        for option in self.selection_options().unwrap_or_default() {
+            let stored = self.stored_option(&option);
+            if let Some(first) = seen.get(&stored.to_lowercase()) {
+                return Err(InvalidFieldType::DuplicateStoredOption {
+                    option,
+                    first: first.clone(),
+                    stored,
+                });
+            }
+            seen.insert(stored.to_lowercase(), option);
+        }
+
+        Ok(())
+    }
+
+    /// types without input types ignore the name, as they ignore an id
+    fn input_type_id_by_name(
+        field_type_id: u8,
+        name: &str,
+    ) -> Result<Option<u8>, InvalidFieldType> {
+        let unknown =
+            |field_type| move |name| InvalidFieldType::UnknownInputType { field_type, name };
+        let input_type_id = match field_type_id {
+            0 => find_by_name(name, TextInputType::NAMES)
+                .map_err(unknown("text"))?
+                .input_type_id(),
+            2 => find_by_name(name, SingleSelectInputType::NAMES)
+                .map_err(unknown("single_select"))?
+                .input_type_id(),
+            4 => find_by_name(name, DateInputType::NAMES)
+                .map_err(unknown("date"))?
+                .input_type_id(),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(input_type_id))
+    }
+
     pub fn new(
         field_type_id: u8,
         input_type_id: Option<u8>,
-        selection_options: Vec<String>,
+        selection_options: SelectionOptions,
     ) -> Result<Self, InvalidFieldType> {
         match field_type_id {
             0 => {
@@ -69,11 +202,15 @@ impl FieldType {
             2 => {
                 let input_type_id = input_type_id.unwrap_or(0);
                 let input_type = SingleSelectInputType::try_from(input_type_id)?;
+                if selection_options.is_tree() && input_type != SingleSelectInputType::Tree {
+                    return Err(InvalidFieldType::NestedOptions);
+                }
                 Ok(Self::SingleSelect {
                     input_type,
                     selection_options,
                 })
             }
+            3 if selection_options.is_tree() => Err(InvalidFieldType::NestedOptions),
             3 => Ok(FieldType::MultiSelect { selection_options }),
             4 => {
                 let input_type_id = input_type_id.unwrap_or(0);
@@ -104,23 +241,53 @@ impl FieldType {
         match self {
             FieldType::SingleSelect {
                 selection_options, ..
-            } => Some(selection_options.clone()),
-            FieldType::MultiSelect { selection_options } => Some(selection_options.clone()),
+            } => Some(selection_options.to_strings()),
+            FieldType::MultiSelect { selection_options } => Some(selection_options.to_strings()),
+            _ => None,
+        }
+    }
+
+    /// the options of a tree dropdown when they nest, which `new_values` cannot express
+    /// a tree dropdown with only top level options is sent like any other select
+    pub fn option_tree(&self) -> Option<&SelectionOptions> {
+        match self {
+            FieldType::SingleSelect {
+                input_type: SingleSelectInputType::Tree,
+                selection_options,
+            } if selection_options.is_tree() => Some(selection_options),
             _ => None,
         }
     }
 
     /// returns options as one comma separated string
-    /// removing commas from options themselves
-    /// avoiding conflicts when posting to halo
+    /// the way halo expects them in `new_values`
     pub fn selection_options_string(&self) -> Option<String> {
-        self.selection_options().map(|options| {
-            options
-                .into_iter()
-                .map(|option| option.replace(",", ""))
-                .collect::<Vec<String>>()
-                .join(", ")
-        })
+        self.selection_options()
+            .map(|options| Self::options_string(&options))
+    }
+
+    /// joins any set of options the way halo expects them
+    pub fn options_string(options: &[String]) -> String {
+        options
+            .iter()
+            .map(|option| Self::halo_option(option))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// an option as halo will store it
+    /// commas separate options in `new_values`, the only confirmed payload, so they cannot be kept
+    pub fn halo_option(option: &str) -> String {
+        option.replace(',', "")
+    }
+
+    /// an option of this field as halo will store it
+    /// nested options are never sent in `new_values`, so only flat options lose their commas
+    pub fn stored_option(&self, option: &str) -> String {
+        match self.option_tree() {
+            Some(_) => option.to_string(),
+            None => Self::halo_option(option),
+        }
     }
 
     pub fn field_type_id(&self) -> u8 {
@@ -136,3 +303,69 @@ impl FieldType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(input_type_id: u8, option: &str) -> FieldType {
+        let options = SelectionOptions::new(vec![option.to_string()]).unwrap();
+        FieldType::new(2, Some(input_type_id), options).unwrap()
+    }
+
+    #[test]
+    fn flat_options_lose_their_commas() {
+        assert_eq!(
+            select(0, "Laptop, Dell").stored_option("Laptop, Dell"),
+            "Laptop Dell"
+        );
+        assert_eq!(
+            select(1, "Laptop, Dell").stored_option("Laptop, Dell"),
+            "Laptop Dell"
+        );
+    }
+
+    #[test]
+    fn nested_options_keep_their_commas() {
+        let field_type = select(1, "Hardware > Laptop, Dell");
+
+        assert!(field_type.option_tree().is_some());
+        assert_eq!(field_type.stored_option("Laptop, Dell"), "Laptop, Dell");
+    }
+
+    #[test]
+    fn nested_options_need_the_tree_input_type() {
+        let nested = || SelectionOptions::new(vec!["Hardware > Laptop".to_string()]).unwrap();
+
+        assert!(FieldType::new(2, Some(1), nested()).is_ok());
+        assert!(matches!(
+            FieldType::new(2, Some(0), nested()),
+            Err(InvalidFieldType::NestedOptions)
+        ));
+        assert!(matches!(
+            FieldType::new(3, None, nested()),
+            Err(InvalidFieldType::NestedOptions)
+        ));
+    }
+
+    #[test]
+    fn rejects_options_that_halo_would_store_the_same() {
+        let options = vec!["Smith, John".to_string(), "smith John".to_string()];
+
+        assert!(matches!(
+            FieldType::from_references(&TypeReference::Id(2), None, options),
+            Err(InvalidFieldType::DuplicateStoredOption { option, first, stored })
+                if option == "smith John" && first == "Smith, John" && stored == "smith John"
+        ));
+    }
+
+    #[test]
+    fn nested_options_are_not_compared_without_commas() {
+        let options = vec!["A > Smith, John".to_string(), "A > Smith John".to_string()];
+
+        assert!(
+            FieldType::from_references(&TypeReference::Id(2), Some(&TypeReference::Id(1)), options)
+                .is_ok()
+        );
+    }
+}
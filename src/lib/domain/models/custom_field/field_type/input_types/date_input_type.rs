@@ -12,6 +12,15 @@ pub enum DateInputType {
 }
 
 impl DateInputType {
+    /// names accepted in source files, the canonical name first
+    pub const NAMES: &'static [(Self, &'static [&'static str])] = &[
+        (DateInputType::Date, &["date", "date_only"]),
+        (
+            DateInputType::DateTime,
+            &["datetime", "date_time", "date_and_time"],
+        ),
+    ];
+
     pub fn input_type_id(&self) -> u8 {
         match self {
             DateInputType::Date => 0,
@@ -13,6 +13,19 @@ pub enum SingleSelectInputType {
 }
 
 impl SingleSelectInputType {
+    /// names accepted in source files, the canonical name first
+    pub const NAMES: &'static [(Self, &'static [&'static str])] = &[
+        (
+            SingleSelectInputType::Standard,
+            &["standard", "dropdown", "standard_dropdown"],
+        ),
+        (SingleSelectInputType::Tree, &["tree", "tree_dropdown"]),
+        (
+            SingleSelectInputType::Radio,
+            &["radio", "radio_button", "radio_selection"],
+        ),
+    ];
+
     pub fn input_type_id(&self) -> u8 {
         match self {
             SingleSelectInputType::Standard => 0,
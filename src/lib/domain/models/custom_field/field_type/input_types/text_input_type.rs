@@ -17,6 +17,20 @@ pub enum TextInputType {
 }
 
 impl TextInputType {
+    /// names accepted in source files, the canonical name first
+    pub const NAMES: &'static [(Self, &'static [&'static str])] = &[
+        (TextInputType::Anything, &["anything", "any", "text"]),
+        (
+            TextInputType::Integer,
+            &["integer", "int", "number", "whole_number"],
+        ),
+        (TextInputType::Money, &["money", "currency"]),
+        (TextInputType::Alphanumeric, &["alphanumeric", "alnum"]),
+        (TextInputType::Decimal, &["decimal", "float"]),
+        (TextInputType::URL, &["url", "link", "web_address"]),
+        (TextInputType::Password, &["password", "masked"]),
+    ];
+
     pub fn input_type_id(&self) -> u8 {
         match self {
             TextInputType::Anything => 0,
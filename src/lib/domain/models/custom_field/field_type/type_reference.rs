@@ -0,0 +1,218 @@
+use serde::{Deserialize, de};
+use std::fmt::Display;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// how close a misspelt name must be to a known one to be suggested
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// a field or input type as written in a source file
+/// either the halo id or one of the names it is known by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeReference {
+    Id(u8),
+    Name(String),
+}
+
+/// anything made only of digits is taken as an id
+/// so an out of range id is reported as a number rather than an unknown name
+impl FromStr for TypeReference {
+    type Err = ParseIntError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+            value.parse().map(TypeReference::Id)
+        } else {
+            Ok(TypeReference::Name(value.to_string()))
+        }
+    }
+}
+
+impl From<u8> for TypeReference {
+    fn from(id: u8) -> Self {
+        TypeReference::Id(id)
+    }
+}
+
+impl<'de> Deserialize<'de> for TypeReference {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct TypeReferenceVisitor;
+
+        impl de::Visitor<'_> for TypeReferenceVisitor {
+            type Value = TypeReference;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a type id or name")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                u8::try_from(value)
+                    .map(TypeReference::Id)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                u8::try_from(value)
+                    .map(TypeReference::Id)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(TypeReferenceVisitor)
+    }
+}
+
+/// a name that matches no known type
+/// with the closest known name when one is close enough to be a typo
+#[derive(Debug)]
+pub struct UnknownName {
+    pub name: String,
+    pub suggestion: Option<&'static str>,
+    pub expected: Vec<&'static str>,
+}
+
+impl Display for UnknownName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.suggestion {
+            Some(suggestion) => write!(f, "'{}', did you mean '{}'?", self.name, suggestion),
+            None => write!(
+                f,
+                "'{}', expected an id or one of: {}",
+                self.name,
+                self.expected.join(", ")
+            ),
+        }
+    }
+}
+
+/// finds a type by any of its names, ignoring case and treating spaces and dashes as underscores
+/// `names` lists each type with its canonical name first and its aliases after
+pub fn find_by_name<T: Clone>(
+    name: &str,
+    names: &[(T, &[&'static str])],
+) -> Result<T, UnknownName> {
+    let normalised = name.trim().to_lowercase().replace([' ', '-'], "_");
+    if let Some((found, _)) = names
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&normalised.as_str()))
+    {
+        return Ok(found.clone());
+    }
+
+    // a whole word of a name, such as `multi` in `multi_select`, beats a close spelling
+    // which would prefer `multiline` for `multi` on the shared prefix alone
+    let word_match = names
+        .iter()
+        .find(|(_, aliases)| {
+            aliases
+                .iter()
+                .any(|alias| alias.split('_').any(|word| word == normalised))
+        })
+        .map(|(_, aliases)| aliases[0]);
+    let suggestion = word_match.or_else(|| {
+        names
+            .iter()
+            .flat_map(|(_, aliases)| aliases.iter())
+            .map(|alias| (*alias, strsim::jaro_winkler(&normalised, alias)))
+            .filter(|(_, similarity)| *similarity >= SUGGESTION_THRESHOLD)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(alias, _)| alias)
+    });
+
+    Err(UnknownName {
+        name: name.trim().to_string(),
+        suggestion,
+        expected: names.iter().map(|(_, aliases)| aliases[0]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::custom_field::field_type::FieldType;
+    use crate::domain::models::custom_field::field_type::input_types::text_input_type::TextInputType;
+    use crate::domain::models::custom_field::usage::FieldUsage;
+
+    fn suggestion(name: &str) -> Option<&'static str> {
+        find_by_name(name, FieldType::NAMES).unwrap_err().suggestion
+    }
+
+    #[test]
+    fn digits_are_ids_and_anything_else_is_a_name() {
+        assert_eq!("2".parse(), Ok(TypeReference::Id(2)));
+        assert_eq!(" 10 ".parse(), Ok(TypeReference::Id(10)));
+        assert_eq!(
+            "single select".parse(),
+            Ok(TypeReference::Name("single select".to_string()))
+        );
+        assert_eq!("-1".parse(), Ok(TypeReference::Name("-1".to_string())));
+        assert!("300".parse::<TypeReference>().is_err());
+    }
+
+    #[test]
+    fn deserializes_numbers_as_ids_and_strings_by_parsing() {
+        let parse = |json: &str| serde_json::from_str::<TypeReference>(json);
+
+        assert_eq!(parse("4").unwrap(), TypeReference::Id(4));
+        assert_eq!(parse("\"4\"").unwrap(), TypeReference::Id(4));
+        assert_eq!(
+            parse("\"date\"").unwrap(),
+            TypeReference::Name("date".to_string())
+        );
+        assert!(parse("-1").is_err());
+        assert!(parse("256").is_err());
+    }
+
+    #[test]
+    fn finds_canonical_names_and_aliases_in_any_case_and_spelling() {
+        assert_eq!(find_by_name("single_select", FieldType::NAMES).unwrap(), 2);
+        assert_eq!(find_by_name("Dropdown", FieldType::NAMES).unwrap(), 2);
+        assert_eq!(find_by_name(" Multi Select ", FieldType::NAMES).unwrap(), 3);
+        assert_eq!(find_by_name("rich-text", FieldType::NAMES).unwrap(), 10);
+        assert_eq!(
+            find_by_name("Whole Number", TextInputType::NAMES).unwrap(),
+            TextInputType::Integer
+        );
+        assert_eq!(
+            find_by_name("customer", FieldUsage::NAMES).unwrap(),
+            FieldUsage::Clients
+        );
+    }
+
+    #[test]
+    fn suggests_the_type_a_word_of_the_name_belongs_to() {
+        assert_eq!(suggestion("multi"), Some("multi_select"));
+        assert_eq!(suggestion("single"), Some("single_select"));
+    }
+
+    #[test]
+    fn suggests_a_close_spelling() {
+        assert_eq!(suggestion("chekbox"), Some("checkbox"));
+        assert_eq!(
+            find_by_name("pasword", TextInputType::NAMES)
+                .unwrap_err()
+                .suggestion,
+            Some("password")
+        );
+    }
+
+    #[test]
+    fn lists_the_canonical_names_when_nothing_is_close() {
+        let error = find_by_name("spreadsheet", FieldType::NAMES).unwrap_err();
+
+        assert_eq!(error.suggestion, None);
+        assert_eq!(
+            error.to_string(),
+            "'spreadsheet', expected an id or one of: \
+             text, memo, single_select, multi_select, date, time, checkbox, rich"
+        );
+    }
+}
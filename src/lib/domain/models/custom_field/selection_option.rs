@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::domain::models::custom_field::field_type::InvalidFieldType;
+
+/// in characters rather than bytes, so accented options get the same room
+const MAX_OPTION_LENGTH: usize = 256;
+
+/// separates a parent option from the options nested under it, e.g. `Hardware > Laptop`
+/// the spaces are required so options such as `>100 users` stay whole
+pub const PATH_SEPARATOR: &str = " > ";
+
+/// one choice of a selection field, trimmed of surrounding whitespace
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SelectionOption(String);
+
+impl SelectionOption {
+    /// `position` counts from 1 and only serves the error
+    pub fn new(option: &str, position: usize) -> Result<Self, InvalidFieldType> {
+        let trimmed = option.trim();
+        if trimmed.is_empty() {
+            Err(InvalidFieldType::EmptyOption { position })
+        } else if trimmed.chars().count() > MAX_OPTION_LENGTH {
+            Err(InvalidFieldType::OptionTooLong { position })
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for SelectionOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// one choice with the choices nested under it
+/// only tree dropdowns have children
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OptionNode {
+    pub option: SelectionOption,
+    pub children: Vec<OptionNode>,
+}
+
+/// the choices of a selection field in the order given
+/// no two siblings differ only by case, since halo would show them as the same choice
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SelectionOptions(Vec<OptionNode>);
+
+impl SelectionOptions {
+    /// each option is a path from the top of the tree, so flat options are paths of one step
+    /// parents are created as needed, `Hardware > Laptop` needs no separate `Hardware`
+    pub fn new(options: Vec<String>) -> Result<Self, InvalidFieldType> {
+        let mut seen: HashMap<String, String> = HashMap::new();
+        let mut nodes = Vec::new();
+        for (index, option) in options.iter().enumerate() {
+            let path = option
+                .split(PATH_SEPARATOR)
+                .map(|step| SelectionOption::new(step, index + 1))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let joined = Self::join(&path);
+            if let Some(first) = seen.get(&joined.to_lowercase()) {
+                return Err(InvalidFieldType::DuplicateOption {
+                    option: joined,
+                    first: first.clone(),
+                });
+            }
+            seen.insert(joined.to_lowercase(), joined);
+
+            Self::insert(&mut nodes, path)?;
+        }
+
+        Ok(Self(nodes))
+    }
+
+    /// options as halo already holds them, each as its path from the top of the tree
+    /// kept as they are, since halo rather than the source file decides what exists
+    pub fn existing(paths: Vec<Vec<String>>) -> Self {
+        let mut nodes = Vec::new();
+        for path in paths {
+            let mut siblings = &mut nodes;
+            for step in path {
+                let index = match siblings
+                    .iter()
+                    .position(|node: &OptionNode| node.option.as_str() == step)
+                {
+                    Some(index) => index,
+                    None => {
+                        siblings.push(OptionNode {
+                            option: SelectionOption(step),
+                            children: Vec::new(),
+                        });
+                        siblings.len() - 1
+                    }
+                };
+                siblings = &mut siblings[index].children;
+            }
+        }
+
+        Self(nodes)
+    }
+
+    fn insert(
+        nodes: &mut Vec<OptionNode>,
+        path: Vec<SelectionOption>,
+    ) -> Result<(), InvalidFieldType> {
+        let mut siblings = nodes;
+        for option in path {
+            let lowercase = option.as_str().to_lowercase();
+            let index = match siblings
+                .iter()
+                .position(|node| node.option.as_str().to_lowercase() == lowercase)
+            {
+                Some(index) if siblings[index].option != option => {
+                    return Err(InvalidFieldType::DuplicateOption {
+                        option: option.to_string(),
+                        first: siblings[index].option.to_string(),
+                    });
+                }
+                Some(index) => index,
+                None => {
+                    siblings.push(OptionNode {
+                        option,
+                        children: Vec::new(),
+                    });
+                    siblings.len() - 1
+                }
+            };
+            siblings = &mut siblings[index].children;
+        }
+
+        Ok(())
+    }
+
+    fn join(path: &[SelectionOption]) -> String {
+        path.iter()
+            .map(SelectionOption::as_str)
+            .collect::<Vec<_>>()
+            .join(PATH_SEPARATOR)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// true when any option has options nested under it
+    pub fn is_tree(&self) -> bool {
+        self.0.iter().any(|node| !node.children.is_empty())
+    }
+
+    /// the options at the top of the tree, or every option of a flat list
+    pub fn nodes(&self) -> &[OptionNode] {
+        &self.0
+    }
+
+    /// the path of every option, parents before their children
+    /// so a flat list comes back as given
+    pub fn to_strings(&self) -> Vec<String> {
+        fn walk(nodes: &[OptionNode], parent: Option<&str>, paths: &mut Vec<String>) {
+            for node in nodes {
+                let path = match parent {
+                    Some(parent) => format!("{}{}{}", parent, PATH_SEPARATOR, node.option),
+                    None => node.option.to_string(),
+                };
+                paths.push(path.clone());
+                walk(&node.children, Some(&path), paths);
+            }
+        }
+
+        let mut paths = Vec::new();
+        walk(&self.0, None, &mut paths);
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_counts_characters_not_bytes() {
+        let accented = "é".repeat(MAX_OPTION_LENGTH);
+        assert!(SelectionOption::new(&accented, 1).is_ok());
+    }
+
+    #[test]
+    fn rejects_options_over_the_limit() {
+        let long = "é".repeat(MAX_OPTION_LENGTH + 1);
+        assert!(matches!(
+            SelectionOption::new(&long, 3),
+            Err(InvalidFieldType::OptionTooLong { position: 3 })
+        ));
+    }
+
+    fn options(options: &[&str]) -> Result<SelectionOptions, InvalidFieldType> {
+        SelectionOptions::new(options.iter().map(|option| option.to_string()).collect())
+    }
+
+    #[test]
+    fn flat_options_keep_their_order() {
+        let options = options(&[" small ", "medium", ">100 users"]).unwrap();
+
+        assert!(!options.is_tree());
+        assert_eq!(options.to_strings(), ["small", "medium", ">100 users"]);
+    }
+
+    #[test]
+    fn paths_build_a_tree_with_implied_parents() {
+        let options = options(&[
+            "Hardware > Laptop > Dell",
+            "Hardware > Desktop",
+            "Hardware > Laptop > HP",
+            "Software",
+        ])
+        .unwrap();
+
+        assert!(options.is_tree());
+        assert_eq!(options.nodes().len(), 2);
+        assert_eq!(
+            options.to_strings(),
+            [
+                "Hardware",
+                "Hardware > Laptop",
+                "Hardware > Laptop > Dell",
+                "Hardware > Laptop > HP",
+                "Hardware > Desktop",
+                "Software",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_options_that_differ_only_by_case() {
+        assert!(matches!(
+            options(&["Small", "small"]),
+            Err(InvalidFieldType::DuplicateOption { option, first })
+                if option == "small" && first == "Small"
+        ));
+    }
+
+    #[test]
+    fn rejects_parents_that_differ_only_by_case() {
+        assert!(matches!(
+            options(&["Hardware > Laptop", "hardware > Desktop"]),
+            Err(InvalidFieldType::DuplicateOption { option, first })
+                if option == "hardware" && first == "Hardware"
+        ));
+    }
+
+    #[test]
+    fn rejects_an_empty_step_in_a_path() {
+        assert!(matches!(
+            options(&["small", "Hardware >  > Dell"]),
+            Err(InvalidFieldType::EmptyOption { position: 2 })
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::models::custom_field::field_type::{
+    FieldType,
+    input_types::text_input_type::TextInputType,
+    type_reference::{TypeReference, UnknownName},
+};
+use crate::domain::models::custom_field::usage::FieldUsage;
+
+#[derive(Debug, Error)]
+pub enum InvalidFieldSettings {
+    #[error("usage must be a positive id")]
+    Usage,
+    #[error("unknown usage {0}")]
+    UnknownUsage(UnknownName),
+    #[error("password fields cannot be searchable")]
+    SearchablePassword { setting: &'static str },
+    #[error("copytochildonupdate needs copytochild")]
+    CopyOnUpdateWithoutCopy,
+}
+
+/// settings as given in a source file
+/// missing ones take their defaults
+#[derive(Debug, Clone, Default)]
+pub struct RequestedSettings {
+    pub usage: Option<TypeReference>,
+    pub searchable: Option<bool>,
+    pub user_searchable: Option<bool>,
+    pub calendar_searchable: Option<bool>,
+    pub copy_to_child: Option<bool>,
+    pub copy_to_child_on_update: Option<bool>,
+}
+
+/// how halo uses a field beyond its type
+/// only sent when a field is created
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldSettings {
+    pub usage: FieldUsage,
+    pub searchable: bool,
+    pub user_searchable: bool,
+    pub calendar_searchable: bool,
+    pub copy_to_child: bool,
+    pub copy_to_child_on_update: bool,
+}
+
+impl FieldSettings {
+    /// password fields default to staying out of search and cannot be put into it
+    pub fn new(
+        requested: RequestedSettings,
+        field_type: &FieldType,
+    ) -> Result<Self, InvalidFieldSettings> {
+        let usage = requested
+            .usage
+            .as_ref()
+            .map(FieldUsage::from_reference)
+            .transpose()?
+            .unwrap_or_default();
+
+        let sensitive = Self::is_sensitive(field_type);
+        if sensitive {
+            for (setting, value) in [
+                ("searchable", requested.searchable),
+                ("user_searchable", requested.user_searchable),
+                ("calendar_searchable", requested.calendar_searchable),
+            ] {
+                if value == Some(true) {
+                    return Err(InvalidFieldSettings::SearchablePassword { setting });
+                }
+            }
+        }
+
+        let settings = Self {
+            usage,
+            ..Self::existing(requested, field_type)
+        };
+        if settings.copy_to_child_on_update && !settings.copy_to_child {
+            return Err(InvalidFieldSettings::CopyOnUpdateWithoutCopy);
+        }
+
+        Ok(settings)
+    }
+
+    /// settings as halo already holds them, with defaults for any it leaves out
+    /// a usage that is not an entity falls back to tickets
+    pub fn existing(requested: RequestedSettings, field_type: &FieldType) -> Self {
+        let searchable_default = !Self::is_sensitive(field_type);
+        let copy_to_child = requested.copy_to_child.unwrap_or(true);
+        Self {
+            usage: requested
+                .usage
+                .as_ref()
+                .and_then(|usage| FieldUsage::from_reference(usage).ok())
+                .unwrap_or_default(),
+            searchable: requested.searchable.unwrap_or(searchable_default),
+            user_searchable: requested.user_searchable.unwrap_or(searchable_default),
+            calendar_searchable: requested.calendar_searchable.unwrap_or(searchable_default),
+            copy_to_child,
+            // follows copy_to_child, so turning that off alone is enough
+            copy_to_child_on_update: requested.copy_to_child_on_update.unwrap_or(copy_to_child),
+        }
+    }
+
+    fn is_sensitive(field_type: &FieldType) -> bool {
+        matches!(
+            field_type,
+            FieldType::Text {
+                input_type: TextInputType::Password
+            }
+        )
+    }
+}
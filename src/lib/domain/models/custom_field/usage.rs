@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::domain::models::custom_field::field_type::type_reference::{
+    TypeReference, find_by_name,
+};
+use crate::domain::models::custom_field::settings::InvalidFieldSettings;
+
+/// the halo entity a field belongs to
+/// entities without a name here are kept by id
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(from = "u8", into = "u8")]
+pub enum FieldUsage {
+    #[default]
+    Tickets,
+    Clients,
+    Sites,
+    Users,
+    Assets,
+    Contracts,
+    Other(u8),
+}
+
+impl FieldUsage {
+    /// canonical name first, then the aliases accepted in source files
+    pub const NAMES: &[(FieldUsage, &[&'static str])] = &[
+        (FieldUsage::Tickets, &["tickets", "ticket"]),
+        (
+            FieldUsage::Clients,
+            &["clients", "client", "customers", "customer"],
+        ),
+        (FieldUsage::Sites, &["sites", "site"]),
+        (FieldUsage::Users, &["users", "user"]),
+        (
+            FieldUsage::Assets,
+            &["assets", "asset", "devices", "device"],
+        ),
+        (FieldUsage::Contracts, &["contracts", "contract"]),
+    ];
+
+    /// id 0 is not an entity, so it is rejected rather than kept as `Other`
+    pub fn from_reference(reference: &TypeReference) -> Result<Self, InvalidFieldSettings> {
+        match reference {
+            TypeReference::Id(0) => Err(InvalidFieldSettings::Usage),
+            TypeReference::Id(id) => Ok(Self::from(*id)),
+            TypeReference::Name(name) => {
+                find_by_name(name, Self::NAMES).map_err(InvalidFieldSettings::UnknownUsage)
+            }
+        }
+    }
+
+    pub fn id(&self) -> u8 {
+        match self {
+            FieldUsage::Tickets => 1,
+            FieldUsage::Clients => 2,
+            FieldUsage::Sites => 3,
+            FieldUsage::Users => 4,
+            FieldUsage::Assets => 5,
+            FieldUsage::Contracts => 6,
+            FieldUsage::Other(id) => *id,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Self::NAMES
+            .iter()
+            .find(|(usage, _)| usage == self)
+            .map(|(_, aliases)| aliases[0])
+    }
+}
+
+impl From<u8> for FieldUsage {
+    fn from(id: u8) -> Self {
+        Self::NAMES
+            .iter()
+            .map(|(usage, _)| *usage)
+            .find(|usage| usage.id() == id)
+            .unwrap_or(FieldUsage::Other(id))
+    }
+}
+
+impl From<FieldUsage> for u8 {
+    fn from(usage: FieldUsage) -> Self {
+        usage.id()
+    }
+}
+
+impl Display for FieldUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "usage {}", self.id()),
+        }
+    }
+}
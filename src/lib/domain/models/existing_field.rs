@@ -0,0 +1,31 @@
+use crate::domain::models::custom_field::{CustomField, usage::FieldUsage};
+
+/// a custom field already present on the halo instance
+#[derive(Debug, Clone)]
+pub struct ExistingField {
+    pub id: i64,
+    pub name: String,
+    pub usage: FieldUsage,
+    /// none when halo holds a definition this tool cannot model
+    pub field: Option<CustomField>,
+}
+
+impl ExistingField {
+    /// halo treats field names case insensitively
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// true when `field` would be this field, names only need to be unique within an entity
+    pub fn matches(&self, field: &CustomField) -> bool {
+        self.usage == field.settings.usage && self.has_name(&field.name.to_string())
+    }
+
+    /// the modeled label, falling back to the name
+    pub fn label(&self) -> String {
+        self.field
+            .as_ref()
+            .map(|field| field.label.to_string())
+            .unwrap_or_else(|| self.name.clone())
+    }
+}
@@ -1 +1,2 @@
 pub mod custom_field;
+pub mod existing_field;
@@ -0,0 +1,215 @@
+use crate::domain::diff::{self, FieldChange};
+use crate::domain::models::custom_field::{CustomField, usage::FieldUsage};
+use crate::domain::models::existing_field::ExistingField;
+
+#[derive(Debug, Clone)]
+pub enum PlannedAction {
+    Create(CustomField),
+    Update {
+        id: i64,
+        field: CustomField,
+        changes: Vec<FieldChange>,
+    },
+    Unchanged {
+        id: i64,
+        field: CustomField,
+    },
+    /// the name exists but the field cannot be brought in line
+    Conflict {
+        id: i64,
+        field: CustomField,
+        reason: String,
+    },
+    /// on the instance but missing from the source file
+    Orphan(ExistingField),
+}
+
+impl PlannedAction {
+    /// compares one source field with the field of the same name and entity on the instance
+    pub fn of(field: &CustomField, existing_fields: &[ExistingField]) -> Self {
+        let Some(existing) = existing_fields
+            .iter()
+            .find(|existing| existing.matches(field))
+        else {
+            return PlannedAction::Create(field.clone());
+        };
+
+        let Some(current) = &existing.field else {
+            return PlannedAction::Conflict {
+                id: existing.id,
+                field: field.clone(),
+                reason: "existing field uses a definition this tool cannot model".to_string(),
+            };
+        };
+
+        match diff::diff(current, field) {
+            Ok(changes) if changes.is_empty() => PlannedAction::Unchanged {
+                id: existing.id,
+                field: field.clone(),
+            },
+            // new options are sent without ids, so they cannot be placed under existing ones
+            Ok(changes)
+                if field.field_type.option_tree().is_some()
+                    && !diff::added_options(&changes).is_empty() =>
+            {
+                PlannedAction::Conflict {
+                    id: existing.id,
+                    field: field.clone(),
+                    reason:
+                        "options cannot be added to an existing tree dropdown, add them in halo"
+                            .to_string(),
+                }
+            }
+            Ok(changes) => PlannedAction::Update {
+                id: existing.id,
+                field: field.clone(),
+                changes,
+            },
+            Err(mismatch) => PlannedAction::Conflict {
+                id: existing.id,
+                field: field.clone(),
+                reason: format!(
+                    "field type cannot change from {} to {}",
+                    mismatch.from, mismatch.to
+                ),
+            },
+        }
+    }
+
+    /// the entity the action applies to
+    pub fn usage(&self) -> FieldUsage {
+        match self {
+            PlannedAction::Create(field)
+            | PlannedAction::Update { field, .. }
+            | PlannedAction::Unchanged { field, .. }
+            | PlannedAction::Conflict { field, .. } => field.settings.usage,
+            PlannedAction::Orphan(existing) => existing.usage,
+        }
+    }
+}
+
+/// the changes needed to bring the instance in line with the source file
+/// grouped by entity, with the orphans of each entity after its source fields
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub actions: Vec<PlannedAction>,
+}
+
+impl Plan {
+    pub fn new(desired_fields: &[CustomField], existing_fields: &[ExistingField]) -> Self {
+        let mut actions: Vec<PlannedAction> = desired_fields
+            .iter()
+            .map(|field| PlannedAction::of(field, existing_fields))
+            .collect();
+
+        actions.extend(
+            existing_fields
+                .iter()
+                .filter(|existing| !desired_fields.iter().any(|field| existing.matches(field)))
+                .cloned()
+                .map(PlannedAction::Orphan),
+        );
+        actions.sort_by_key(|action| (action.usage(), matches!(action, PlannedAction::Orphan(_))));
+
+        Self { actions }
+    }
+
+    pub fn creates(&self) -> usize {
+        self.count(|action| matches!(action, PlannedAction::Create(_)))
+    }
+
+    pub fn updates(&self) -> usize {
+        self.count(|action| matches!(action, PlannedAction::Update { .. }))
+    }
+
+    pub fn unchanged(&self) -> usize {
+        self.count(|action| matches!(action, PlannedAction::Unchanged { .. }))
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.count(|action| matches!(action, PlannedAction::Conflict { .. }))
+    }
+
+    pub fn orphans(&self) -> usize {
+        self.count(|action| matches!(action, PlannedAction::Orphan(_)))
+    }
+
+    /// true when applying the plan would send nothing
+    pub fn is_empty(&self) -> bool {
+        self.creates() == 0 && self.updates() == 0
+    }
+
+    fn count(&self, matches: fn(&PlannedAction) -> bool) -> usize {
+        self.actions.iter().filter(|action| matches(action)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_support::{existing, field};
+
+    fn kinds(plan: &Plan) -> Vec<(&'static str, FieldUsage)> {
+        plan.actions
+            .iter()
+            .map(|action| {
+                let kind = match action {
+                    PlannedAction::Create(_) => "create",
+                    PlannedAction::Update { .. } => "update",
+                    PlannedAction::Unchanged { .. } => "unchanged",
+                    PlannedAction::Conflict { .. } => "conflict",
+                    PlannedAction::Orphan(_) => "orphan",
+                };
+                (kind, action.usage())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_by_entity_with_orphans_last() {
+        let existing_fields = [
+            existing(1, field("notes", 1, "clients")),
+            existing(2, field("notes", 1, "tickets")),
+            existing(3, field("old", 1, "tickets")),
+        ];
+        let desired = [field("notes", 1, "tickets"), field("budget", 0, "clients")];
+
+        let plan = Plan::new(&desired, &existing_fields);
+
+        assert_eq!(
+            kinds(&plan),
+            [
+                ("unchanged", FieldUsage::Tickets),
+                ("orphan", FieldUsage::Tickets),
+                ("create", FieldUsage::Clients),
+                ("orphan", FieldUsage::Clients),
+            ]
+        );
+        assert!(!plan.is_empty());
+    }
+
+    #[test]
+    fn a_type_change_is_a_conflict() {
+        let existing_fields = [existing(1, field("notes", 1, "tickets"))];
+        let desired = [field("notes", 0, "tickets")];
+
+        let plan = Plan::new(&desired, &existing_fields);
+
+        assert_eq!(kinds(&plan), [("conflict", FieldUsage::Tickets)]);
+        assert_eq!(plan.conflicts(), 1);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn an_unmodeled_field_is_a_conflict() {
+        let existing_fields = [ExistingField {
+            field: None,
+            ..existing(1, field("notes", 1, "tickets"))
+        }];
+        let desired = [field("notes", 1, "tickets")];
+
+        let plan = Plan::new(&desired, &existing_fields);
+
+        assert_eq!(plan.conflicts(), 1);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::domain::models::custom_field::CustomField;
+use crate::domain::models::custom_field::field_type::type_reference::TypeReference;
+use crate::domain::models::custom_field::settings::RequestedSettings;
+use crate::domain::models::custom_field::usage::FieldUsage;
+use crate::domain::models::existing_field::ExistingField;
+
+/// a ticket field named `pizzaSize` with the given options
+pub fn single_select(label: &str, input_type_id: u8, options: &[&str]) -> CustomField {
+    CustomField::new(
+        "pizzaSize",
+        label,
+        TypeReference::Id(2),
+        Some(TypeReference::Id(input_type_id)),
+        options.iter().map(|option| option.to_string()).collect(),
+        RequestedSettings::default(),
+    )
+    .unwrap()
+}
+
+/// a field without options for `usage`, labelled with its name
+pub fn field(name: &str, field_type_id: u8, usage: &str) -> CustomField {
+    CustomField::new(
+        name,
+        name,
+        TypeReference::Id(field_type_id),
+        None,
+        Vec::new(),
+        RequestedSettings {
+            usage: Some(TypeReference::Name(usage.to_string())),
+            ..RequestedSettings::default()
+        },
+    )
+    .unwrap()
+}
+
+/// `field` as halo lists it under `id`
+pub fn existing(id: i64, field: CustomField) -> ExistingField {
+    ExistingField {
+        id,
+        name: field.name.to_string(),
+        usage: field.settings.usage,
+        field: Some(field),
+    }
+}
+
+/// a field halo lists under `id` with a definition this tool cannot model
+pub fn unmodeled(id: i64, usage: FieldUsage, name: &str) -> ExistingField {
+    ExistingField {
+        id,
+        name: name.to_string(),
+        usage,
+        field: None,
+    }
+}
@@ -0,0 +1,217 @@
+use colored::*;
+use log::warn;
+use std::fmt::Display;
+
+use crate::domain::models::custom_field::CustomField;
+use crate::hprintln;
+
+/// where in the source file an issue was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueLocation {
+    /// csv rows, counting the header as row 1
+    Row(usize),
+    /// zero based position in the `fields` list of a structured document
+    Entry(usize),
+    /// an entry in a document that keeps line and column numbers
+    Line {
+        entry: usize,
+        line: usize,
+        column: usize,
+    },
+    /// a row of a workbook sheet, counting from 1 like the sheet does
+    /// with the zero based column of the cell when the issue is in one
+    Cell {
+        sheet: String,
+        row: usize,
+        column: Option<usize>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ValidationIssue {
+    pub location: IssueLocation,
+    /// the csv column or document key
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.location, &self.column) {
+            (IssueLocation::Row(row), Some(column)) => {
+                write!(f, "row {}, column '{}': {}", row, column, self.message)
+            }
+            (IssueLocation::Row(row), None) => write!(f, "row {}: {}", row, self.message),
+            (IssueLocation::Entry(index), Some(key)) => {
+                write!(f, "fields[{}].{}: {}", index, key, self.message)
+            }
+            (IssueLocation::Entry(index), None) => {
+                write!(f, "fields[{}]: {}", index, self.message)
+            }
+            (
+                IssueLocation::Line {
+                    entry,
+                    line,
+                    column,
+                },
+                Some(key),
+            ) => write!(
+                f,
+                "fields[{}].{} (line {}, column {}): {}",
+                entry, key, line, column, self.message
+            ),
+            (
+                IssueLocation::Line {
+                    entry,
+                    line,
+                    column,
+                },
+                None,
+            ) => write!(
+                f,
+                "fields[{}] (line {}, column {}): {}",
+                entry, line, column, self.message
+            ),
+            (
+                IssueLocation::Cell {
+                    sheet,
+                    row,
+                    column: Some(cell_column),
+                },
+                Some(column),
+            ) => write!(
+                f,
+                "sheet '{}', row {}, cell {}{}, column '{}': {}",
+                sheet,
+                row,
+                column_letters(*cell_column),
+                row,
+                column,
+                self.message
+            ),
+            (IssueLocation::Cell { sheet, row, .. }, Some(column)) => write!(
+                f,
+                "sheet '{}', row {}, column '{}': {}",
+                sheet, row, column, self.message
+            ),
+            (IssueLocation::Cell { sheet, row, .. }, None) => {
+                write!(f, "sheet '{}', row {}: {}", sheet, row, self.message)
+            }
+        }
+    }
+}
+
+/// spreadsheet column letters for a zero based column, e.g. 27 is AB
+fn column_letters(column: usize) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = column + 1;
+    while remaining > 0 {
+        let letter = (remaining - 1) % 26;
+        letters.push(char::from(b'A' + letter as u8));
+        remaining = (remaining - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// every field that passed validation
+/// alongside every issue found in the source file
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub fields: Vec<CustomField>,
+    pub issues: Vec<ValidationIssue>,
+    /// problems that do not stop a field being created, such as options halo will change
+    pub warnings: Vec<ValidationIssue>,
+    pub rows_checked: usize,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_field(&mut self, field: CustomField) {
+        self.fields.push(field);
+    }
+
+    pub fn add_issue(
+        &mut self,
+        location: IssueLocation,
+        column: Option<&str>,
+        message: impl Display,
+    ) {
+        self.issues.push(ValidationIssue {
+            location,
+            column: column.map(String::from),
+            message: message.to_string(),
+        });
+    }
+
+    pub fn add_warning(
+        &mut self,
+        location: IssueLocation,
+        column: Option<&str>,
+        message: impl Display,
+    ) {
+        self.warnings.push(ValidationIssue {
+            location,
+            column: column.map(String::from),
+            message: message.to_string(),
+        });
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// returns the fields when no issues were found
+    pub fn into_fields(self) -> anyhow::Result<Vec<CustomField>> {
+        if !self.is_valid() {
+            anyhow::bail!(
+                "source file failed validation with {} issue(s)",
+                self.issues.len()
+            );
+        }
+        Ok(self.fields)
+    }
+
+    /// logs each warning, so they reach the log file and stay off stdout
+    pub fn log_warnings(&self) {
+        for warning in &self.warnings {
+            warn!("{}", warning);
+        }
+    }
+
+    pub fn log_summary(&self) {
+        hprintln!("\n{}", "Validation Summary:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
+
+        hprintln!(
+            "• Rows checked: {}",
+            self.rows_checked.to_string().bright_yellow()
+        );
+        hprintln!(
+            "• Valid fields: {}",
+            self.fields.len().to_string().bright_green()
+        );
+        hprintln!(
+            "• Issues found: {}",
+            self.issues.len().to_string().bright_red()
+        );
+
+        if !self.issues.is_empty() {
+            hprintln!("\n{}", "Issues:".bright_red().bold());
+            for issue in &self.issues {
+                hprintln!("• {}", issue.to_string().bright_red());
+            }
+        }
+
+        if !self.warnings.is_empty() {
+            hprintln!("\n{}", "Warnings:".bright_yellow().bold());
+            for warning in &self.warnings {
+                hprintln!("• {}", warning.to_string().bright_yellow());
+            }
+        }
+
+        hprintln!("{}", "=".repeat(80).bright_blue());
+    }
+}
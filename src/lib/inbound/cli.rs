@@ -0,0 +1,198 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::config::ConfigOverrides;
+use crate::domain::collision::ConflictPolicy;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "halo_custom_field_builder",
+    version,
+    about = "bulk creation of custom fields in Halo from a source file"
+)]
+pub struct Cli {
+    /// source file with field definitions (overrides SOURCE_FILE_NAME)
+    #[arg(long, short, global = true)]
+    pub source: Option<String>,
+
+    /// workbook sheet to read fields from, the first sheet by default (overrides SOURCE_SHEET)
+    #[arg(long, global = true)]
+    pub sheet: Option<String>,
+
+    /// character separating selection options in one cell, a comma by default (overrides OPTION_DELIMITER)
+    #[arg(long, global = true)]
+    pub option_delimiter: Option<String>,
+
+    /// Halo instance url (overrides BASE_URL)
+    #[arg(long, global = true)]
+    pub base_url: Option<String>,
+
+    /// named instance from the profiles file (overrides PROFILE)
+    #[arg(long, short, global = true)]
+    pub profile: Option<String>,
+
+    /// profiles file to read instead of profiles.toml (overrides PROFILES_FILE)
+    #[arg(long, global = true)]
+    pub profiles_file: Option<PathBuf>,
+
+    /// environment file to load instead of .env
+    #[arg(long, global = true)]
+    pub env_file: Option<PathBuf>,
+
+    /// tries per request before a transient failure is given up on (overrides MAX_ATTEMPTS)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: Option<u32>,
+
+    /// requests allowed in any 5 minute window (overrides REQUEST_BUDGET)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub request_budget: Option<u32>,
+
+    /// confirms writes to this protected profile without prompting, for unattended runs
+    #[arg(long, global = true, value_name = "NAME")]
+    pub confirm_profile: Option<String>,
+
+    /// runs the interactive menu when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// create every field in the source file
+    Import {
+        #[command(flatten)]
+        args: ImportArgs,
+    },
+    /// review and create fields one at a time
+    Debug {
+        #[command(flatten)]
+        args: ImportArgs,
+    },
+    /// validate the source file without contacting Halo
+    Validate,
+    /// compare the source file with the fields on the instance
+    Plan,
+    /// create and update fields so the instance matches the source file
+    Apply {
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+
+        /// apply without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// delete the fields listed in a manifest
+    Delete {
+        /// csv with a `name` column, or the results file of a previous run
+        #[arg(long, short)]
+        manifest: PathBuf,
+
+        #[command(flatten)]
+        dry_run: DryRunArgs,
+
+        /// delete without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+
+        /// also delete fields that no recorded run of this tool created
+        #[arg(long)]
+        allow_untracked: bool,
+
+        /// relabel the fields as archived instead of deleting them, keeping their data
+        #[arg(long, conflicts_with = "allow_data")]
+        archive: bool,
+
+        /// delete even though halo cannot be checked for data the fields hold
+        #[arg(long)]
+        allow_data: bool,
+    },
+    /// write the Halo payload for every field as json
+    Export {
+        /// file to write to instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DryRunArgs {
+    /// run the whole pipeline but write requests out instead of sending them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// file to write dry run requests to instead of stdout
+    #[arg(long, short, requires = "dry_run")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ImportArgs {
+    #[command(flatten)]
+    pub dry_run: DryRunArgs,
+
+    /// what to do with fields whose name already exists on the instance
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: ConflictPolicyArg,
+
+    /// skip fields an earlier run of the same source file already created
+    #[arg(long, conflicts_with = "dry_run")]
+    pub resume: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum ConflictPolicyArg {
+    /// skip every field whose name already exists
+    #[default]
+    Skip,
+    /// skip identical fields and report conflicting ones as failures
+    Flag,
+    /// update conflicting fields to match the source file
+    Update,
+}
+
+impl From<ConflictPolicyArg> for ConflictPolicy {
+    fn from(value: ConflictPolicyArg) -> Self {
+        match value {
+            ConflictPolicyArg::Skip => ConflictPolicy::Skip,
+            ConflictPolicyArg::Flag => ConflictPolicy::Flag,
+            ConflictPolicyArg::Update => ConflictPolicy::Update,
+        }
+    }
+}
+
+impl DryRunArgs {
+    fn writes_to_stdout(&self) -> bool {
+        self.dry_run && self.output.is_none()
+    }
+}
+
+impl Cli {
+    pub fn config_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            env_file: self.env_file.clone(),
+            base_url: self.base_url.clone(),
+            source_file_name: self.source.clone(),
+            source_sheet: self.sheet.clone(),
+            option_delimiter: self.option_delimiter.clone(),
+            max_attempts: self.max_attempts,
+            request_budget: self.request_budget,
+            profile: self.profile.clone(),
+            profiles_file: self.profiles_file.clone(),
+        }
+    }
+
+    /// true when stdout carries program output
+    /// and log lines should go to stderr instead
+    pub fn writes_to_stdout(&self) -> bool {
+        match &self.command {
+            Some(Command::Export { output }) => output.is_none(),
+            Some(Command::Import { args } | Command::Debug { args }) => {
+                args.dry_run.writes_to_stdout()
+            }
+            Some(Command::Apply { dry_run, .. } | Command::Delete { dry_run, .. }) => {
+                dry_run.writes_to_stdout()
+            }
+            _ => false,
+        }
+    }
+}
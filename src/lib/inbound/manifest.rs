@@ -0,0 +1,107 @@
+use anyhow::{Context, anyhow};
+use csv::Reader;
+use std::path::Path;
+
+use crate::domain::deletion::ManifestEntry;
+use crate::domain::import_result::FieldAction;
+use crate::domain::logging;
+use crate::domain::models::custom_field::field_type::type_reference::TypeReference;
+use crate::domain::models::custom_field::usage::FieldUsage;
+
+const NAME_COLUMN: &str = "name";
+const USAGE_COLUMN: &str = "usage";
+
+/// reads the fields to remove
+/// from a csv with a `name` column and an optional `usage` column
+/// or from the json results file of a previous run
+#[derive(Default)]
+pub struct ManifestReader;
+
+impl ManifestReader {
+    pub fn new() -> Self {
+        ManifestReader
+    }
+
+    pub fn read_entries(&self, path: &Path) -> anyhow::Result<Vec<ManifestEntry>> {
+        let entries = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => self.read_results_file(path)?,
+            _ => self.read_csv(path)?,
+        };
+
+        let mut unique_entries: Vec<ManifestEntry> = Vec::new();
+        for entry in entries {
+            if !unique_entries
+                .iter()
+                .any(|unique_entry| unique_entry.key() == entry.key())
+            {
+                unique_entries.push(entry);
+            }
+        }
+
+        Ok(unique_entries)
+    }
+
+    /// only fields the run created are listed, each in the entity it was created for
+    fn read_results_file(&self, path: &Path) -> anyhow::Result<Vec<ManifestEntry>> {
+        let results = logging::load_results(path)?;
+        Ok(results
+            .successful
+            .into_iter()
+            .filter(|result| result.action == FieldAction::Create)
+            .map(|result| ManifestEntry {
+                usage: result.usage,
+                name: result.name,
+            })
+            .collect())
+    }
+
+    /// a missing or empty usage means tickets, as in source files
+    fn read_csv(&self, path: &Path) -> anyhow::Result<Vec<ManifestEntry>> {
+        let mut reader = Reader::from_path(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+
+        let position = reader
+            .headers()
+            .context("failed to read headers")?
+            .iter()
+            .position(|h| h == NAME_COLUMN)
+            .ok_or_else(|| anyhow!("missing '{}' column", NAME_COLUMN))?;
+        let usage_position = reader
+            .headers()
+            .context("failed to read headers")?
+            .iter()
+            .position(|h| h == USAGE_COLUMN);
+
+        let mut entries = Vec::new();
+        for (raw_row_index, result) in reader.records().enumerate() {
+            let row_index = raw_row_index + 2;
+            let row_data = result.context(format!("row {}: failed to read entry", row_index))?;
+
+            let name = row_data[position].trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            let raw_usage = usage_position
+                .and_then(|usage_position| row_data.get(usage_position))
+                .map(str::trim)
+                .unwrap_or_default();
+            let usage = if raw_usage.is_empty() {
+                FieldUsage::default()
+            } else {
+                raw_usage
+                    .parse::<TypeReference>()
+                    .map_err(|e| anyhow!(e))
+                    .and_then(|usage| FieldUsage::from_reference(&usage).map_err(|e| anyhow!(e)))
+                    .with_context(|| format!("row {}: invalid usage '{}'", row_index, raw_usage))?
+            };
+
+            entries.push(ManifestEntry {
+                usage,
+                name: name.to_string(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
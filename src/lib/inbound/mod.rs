@@ -1,2 +1,4 @@
+pub mod cli;
+pub mod manifest;
 pub mod readers;
 pub mod screens;
@@ -1,8 +1,97 @@
-use crate::config::Config;
-use crate::domain::models::custom_field::CustomField;
-use anyhow::{anyhow, Context};
+pub mod definition;
+pub mod json;
+pub mod options;
+pub mod toml;
+pub mod workbook;
+pub mod yaml;
+
+use crate::config::{Config, SourceSettings};
+use crate::domain::models::custom_field::{
+    CustomField, InvalidCustomField,
+    field_type::{InvalidFieldType, type_reference::TypeReference},
+    label::Label,
+    name::Name,
+    settings::{InvalidFieldSettings, RequestedSettings},
+};
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::json::JsonReader;
+use crate::inbound::readers::options::split_options;
+use crate::inbound::readers::toml::TomlReader;
+use crate::inbound::readers::workbook::WorkbookReader;
+use crate::inbound::readers::yaml::YamlReader;
+use anyhow::Context;
 use csv::Reader;
+use std::path::Path;
+
+const NAME_COLUMN: &str = "name";
+const LABEL_COLUMN: &str = "label";
+const FIELD_TYPE_ID_COLUMN: &str = "field_type_id";
+const INPUT_TYPE_ID_COLUMN: &str = "input_type_id";
+pub(crate) const SELECTION_OPTIONS_COLUMN: &str = "selection_options";
+const USAGE_COLUMN: &str = "usage";
+const SEARCHABLE_COLUMN: &str = "searchable";
+const USER_SEARCHABLE_COLUMN: &str = "user_searchable";
+const CALENDAR_SEARCHABLE_COLUMN: &str = "calendar_searchable";
+const COPY_TO_CHILD_COLUMN: &str = "copytochild";
+const COPY_TO_CHILD_ON_UPDATE_COLUMN: &str = "copytochildonupdate";
+
+/// csv headers occupy the first row
+const HEADER_ROW: IssueLocation = IssueLocation::Row(1);
+
+/// reads a source file with the reader matching its extension
+/// csv is assumed when the extension is not recognised
+#[derive(Default)]
+pub struct SourceReader;
+
+impl SourceReader {
+    pub fn new() -> Self {
+        SourceReader
+    }
+
+    pub fn read_fields(&self, config: &Config) -> anyhow::Result<Vec<CustomField>> {
+        self.read_source(config.source_file_name()?, &config.source_settings)
+    }
+
+    /// reads a source file without the rest of the configuration
+    pub fn read_source(
+        &self,
+        source_file_name: &str,
+        source_settings: &SourceSettings,
+    ) -> anyhow::Result<Vec<CustomField>> {
+        let report = self.validate(source_file_name, source_settings)?;
+        if !report.is_valid() {
+            report.log_summary();
+        } else {
+            report.log_warnings();
+        }
+        report.into_fields()
+    }
+
+    /// checks every entry instead of stopping at the first invalid one
+    /// `settings` only apply to csv files and workbooks
+    pub fn validate(
+        &self,
+        path: &str,
+        settings: &SourceSettings,
+    ) -> anyhow::Result<ValidationReport> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("json") => JsonReader::new().validate(path),
+            Some("yaml" | "yml") => YamlReader::new().validate(path),
+            Some("toml") => TomlReader::new().validate(path),
+            Some("xlsx" | "xlsm" | "xlsb" | "xls" | "ods") => {
+                WorkbookReader::new().validate(path, settings)
+            }
+            _ => CsvReader::new().validate(path, settings.option_delimiter),
+        }
+    }
+}
 
+#[derive(Default)]
 pub struct CsvReader;
 
 #[derive(Debug)]
@@ -12,6 +101,33 @@ struct FieldPositions {
     field_type_id: usize,
     input_type_id: usize,
     selection_options: usize,
+    /// settings columns may be left out, their defaults apply instead
+    usage: Option<usize>,
+    searchable: Option<usize>,
+    user_searchable: Option<usize>,
+    calendar_searchable: Option<usize>,
+    copy_to_child: Option<usize>,
+    copy_to_child_on_update: Option<usize>,
+}
+
+impl FieldPositions {
+    /// the position of a column, none for a settings column that was left out or an unknown column
+    fn of(&self, column: &str) -> Option<usize> {
+        match column {
+            NAME_COLUMN => Some(self.name),
+            LABEL_COLUMN => Some(self.label),
+            FIELD_TYPE_ID_COLUMN => Some(self.field_type_id),
+            INPUT_TYPE_ID_COLUMN => Some(self.input_type_id),
+            SELECTION_OPTIONS_COLUMN => Some(self.selection_options),
+            USAGE_COLUMN => self.usage,
+            SEARCHABLE_COLUMN => self.searchable,
+            USER_SEARCHABLE_COLUMN => self.user_searchable,
+            CALENDAR_SEARCHABLE_COLUMN => self.calendar_searchable,
+            COPY_TO_CHILD_COLUMN => self.copy_to_child,
+            COPY_TO_CHILD_ON_UPDATE_COLUMN => self.copy_to_child_on_update,
+            _ => None,
+        }
+    }
 }
 
 impl CsvReader {
@@ -19,85 +135,348 @@ impl CsvReader {
         CsvReader
     }
 
-    fn open_csv(&self, config: &Config) -> anyhow::Result<Reader<std::fs::File>> {
-        Ok(Reader::from_path(&config.source_file_name)?)
+    fn open_csv(&self, path: &str) -> anyhow::Result<Reader<std::fs::File>> {
+        Reader::from_path(path).with_context(|| format!("failed to open {}", path))
     }
 
-    fn get_field_positions(&self, headers: &csv::StringRecord) -> anyhow::Result<FieldPositions> {
-        Ok(FieldPositions {
-            name: headers
-                .iter()
-                .position(|h| h == "name")
-                .ok_or_else(|| anyhow!("missing 'name' column"))?,
+    /// checks every row instead of stopping at the first invalid one
+    pub fn validate(&self, path: &str, option_delimiter: char) -> anyhow::Result<ValidationReport> {
+        let mut report = ValidationReport::new();
+        let mut reader = self.open_csv(path)?;
 
-            label: headers
-                .iter()
-                .position(|h| h == "label")
-                .ok_or_else(|| anyhow!("missing 'label' column"))?,
+        let headers = reader.headers().context("failed to read headers")?.clone();
+        let headers: Vec<&str> = headers.iter().collect();
+        let Some(positions) = get_field_positions(&headers, HEADER_ROW, &mut report) else {
+            return Ok(report);
+        };
 
-            field_type_id: headers
-                .iter()
-                .position(|h| h == "field_type_id")
-                .ok_or_else(|| anyhow!("missing 'field_type_id' column"))?,
+        for (raw_row_index, result) in reader.records().enumerate() {
+            let row_index = raw_row_index + 2;
+            report.rows_checked += 1;
 
-            input_type_id: headers
-                .iter()
-                .position(|h| h == "input_type_id")
-                .ok_or_else(|| anyhow!("missing 'input_type_id' column"))?,
+            let row_data = match result {
+                Ok(row_data) => row_data,
+                Err(e) => {
+                    report.add_issue(
+                        IssueLocation::Row(row_index),
+                        None,
+                        format!("failed to read entry: {}", e),
+                    );
+                    continue;
+                }
+            };
 
-            selection_options: headers
-                .iter()
-                .position(|h| h == "selection_options")
-                .ok_or_else(|| anyhow!("missing 'selection_options' column"))?,
-        })
+            let row: Vec<&str> = row_data.iter().collect();
+            let location = |_| IssueLocation::Row(row_index);
+            let field = parse_row(&row, &positions, option_delimiter, &mut report, location);
+            if let Some(field) = field {
+                report.add_field(field);
+            }
+        }
+
+        Ok(report)
     }
+}
 
-    pub fn read_fields(&self, config: &Config) -> anyhow::Result<Vec<CustomField>> {
-        let mut fields = Vec::new();
-        let mut reader = self.open_csv(config)?;
+/// records every missing required column as an issue on the header row
+fn get_field_positions(
+    headers: &[&str],
+    header_location: IssueLocation,
+    report: &mut ValidationReport,
+) -> Option<FieldPositions> {
+    let mut position = |column: &str| {
+        let position = headers.iter().position(|h| *h == column);
+        if position.is_none() {
+            report.add_issue(header_location.clone(), Some(column), "missing column");
+        }
+        position
+    };
 
-        let headers = reader.headers()?;
-        let positions = self.get_field_positions(headers)?;
+    let name = position(NAME_COLUMN);
+    let label = position(LABEL_COLUMN);
+    let field_type_id = position(FIELD_TYPE_ID_COLUMN);
+    let input_type_id = position(INPUT_TYPE_ID_COLUMN);
+    let selection_options = position(SELECTION_OPTIONS_COLUMN);
+    let optional = |column: &str| headers.iter().position(|h| *h == column);
 
-        for (raw_row_index, result) in reader.records().enumerate() {
-            let row_index = raw_row_index + 2;
-            let row_data = result.context(format!("row {}: failed to read entry", row_index))?;
+    Some(FieldPositions {
+        name: name?,
+        label: label?,
+        field_type_id: field_type_id?,
+        input_type_id: input_type_id?,
+        selection_options: selection_options?,
+        usage: optional(USAGE_COLUMN),
+        searchable: optional(SEARCHABLE_COLUMN),
+        user_searchable: optional(USER_SEARCHABLE_COLUMN),
+        calendar_searchable: optional(CALENDAR_SEARCHABLE_COLUMN),
+        copy_to_child: optional(COPY_TO_CHILD_COLUMN),
+        copy_to_child_on_update: optional(COPY_TO_CHILD_ON_UPDATE_COLUMN),
+    })
+}
 
-            let field_type_id: u8 = row_data[positions.field_type_id]
-                .parse()
-                .context(format!("row {}: invalid field_type_id value", row_index))?;
+/// validates one row of a tabular source
+/// `location` is given the position of the column an issue was found in
+fn parse_row(
+    row: &[&str],
+    positions: &FieldPositions,
+    option_delimiter: char,
+    report: &mut ValidationReport,
+    location: impl Fn(Option<usize>) -> IssueLocation,
+) -> Option<CustomField> {
+    let cell = |position: usize| row.get(position).copied().unwrap_or_default();
+
+    let raw_field_type_id = cell(positions.field_type_id);
+    let field_type_id: Option<TypeReference> = match raw_field_type_id.parse() {
+        Ok(field_type_id) => Some(field_type_id),
+        Err(e) => {
+            report.add_issue(
+                location(Some(positions.field_type_id)),
+                Some(FIELD_TYPE_ID_COLUMN),
+                format!("invalid value '{}': {}", raw_field_type_id, e),
+            );
+            None
+        }
+    };
 
-            let input_type_id: Option<u8> = if row_data[positions.input_type_id].trim().is_empty() {
+    let raw_input_type_id = cell(positions.input_type_id);
+    let input_type_id: Option<Option<TypeReference>> = if raw_input_type_id.trim().is_empty() {
+        Some(None)
+    } else {
+        match raw_input_type_id.parse() {
+            Ok(input_type_id) => Some(Some(input_type_id)),
+            Err(e) => {
+                report.add_issue(
+                    location(Some(positions.input_type_id)),
+                    Some(INPUT_TYPE_ID_COLUMN),
+                    format!("invalid value '{}': {}", raw_input_type_id, e),
+                );
                 None
-            } else {
-                Some(
-                    row_data[positions.input_type_id]
-                        .parse()
-                        .context(format!("row {}: invalid input_type_id value", row_index))?,
-                )
-            };
+            }
+        }
+    };
 
-            let selection_options = if row_data[positions.selection_options].trim().is_empty() {
+    let raw_selection_options = cell(positions.selection_options);
+    let selection_options = if raw_selection_options.trim().is_empty() {
+        Some(vec![])
+    } else {
+        match split_options(raw_selection_options, option_delimiter) {
+            Ok(selection_options) => Some(selection_options),
+            Err(e) => {
+                report.add_issue(
+                    location(Some(positions.selection_options)),
+                    Some(SELECTION_OPTIONS_COLUMN),
+                    format!("invalid value '{}': {}", raw_selection_options, e),
+                );
                 None
-            } else {
-                Some(row_data[positions.selection_options].to_string())
-            };
+            }
+        }
+    };
+
+    let raw_setting = |position: Option<usize>| {
+        position
+            .map(cell)
+            .map(str::trim)
+            .filter(|raw| !raw.is_empty())
+    };
+    let mut invalid_settings = Vec::new();
+    let usage = raw_setting(positions.usage).and_then(|raw| {
+        raw.parse::<TypeReference>()
+            .map_err(|e| invalid_settings.push((USAGE_COLUMN, positions.usage, raw, e.to_string())))
+            .ok()
+    });
+    let mut flag = |column, position| {
+        raw_setting(position).and_then(|raw| {
+            parse_flag(raw)
+                .map_err(|e| invalid_settings.push((column, position, raw, e)))
+                .ok()
+        })
+    };
+    let settings = RequestedSettings {
+        usage,
+        searchable: flag(SEARCHABLE_COLUMN, positions.searchable),
+        user_searchable: flag(USER_SEARCHABLE_COLUMN, positions.user_searchable),
+        calendar_searchable: flag(CALENDAR_SEARCHABLE_COLUMN, positions.calendar_searchable),
+        copy_to_child: flag(COPY_TO_CHILD_COLUMN, positions.copy_to_child),
+        copy_to_child_on_update: flag(
+            COPY_TO_CHILD_ON_UPDATE_COLUMN,
+            positions.copy_to_child_on_update,
+        ),
+    };
+    for (column, position, raw, e) in &invalid_settings {
+        report.add_issue(
+            location(*position),
+            Some(column),
+            format!("invalid value '{}': {}", raw, e),
+        );
+    }
+
+    let (Some(field_type_id), Some(input_type_id), Some(selection_options), true) = (
+        field_type_id,
+        input_type_id,
+        selection_options,
+        invalid_settings.is_empty(),
+    ) else {
+        // the rest of the row can still be checked
+        if let Err(e) = Name::new(cell(positions.name)) {
+            report.add_issue(location(Some(positions.name)), Some(NAME_COLUMN), e);
+        }
+        if let Err(e) = Label::new(cell(positions.label)) {
+            report.add_issue(location(Some(positions.label)), Some(LABEL_COLUMN), e);
+        }
+        return None;
+    };
+
+    match CustomField::validate(
+        cell(positions.name),
+        cell(positions.label),
+        field_type_id,
+        input_type_id,
+        selection_options,
+        settings,
+    ) {
+        Ok(field) => {
+            if let Err(e) = check_unique_name(&field, report) {
+                report.add_issue(location(Some(positions.name)), Some(NAME_COLUMN), e);
+                return None;
+            }
+            warn_lossy_options(&field, report, || {
+                location(Some(positions.selection_options))
+            });
+            Some(field)
+        }
+        Err(problems) => {
+            for e in problems {
+                let column = invalid_field_column(&e);
+                report.add_issue(location(positions.of(column)), Some(column), e);
+            }
+            None
+        }
+    }
+}
+
+/// yes and no in the forms spreadsheets tend to hold them
+fn parse_flag(raw: &str) -> Result<bool, String> {
+    match raw.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" => Ok(true),
+        "false" | "no" | "n" | "0" => Ok(false),
+        _ => Err("expected true or false".to_string()),
+    }
+}
 
-            let field = CustomField::new(
-                &row_data[positions.name],
-                &row_data[positions.label],
-                field_type_id,
-                input_type_id,
-                selection_options,
-            )
-            .context(format!(
-                "row {}: failed to create custom field",
-                row_index + 2
-            ))?;
-
-            fields.push(field);
+/// names only need to be unique within an entity, as halo keeps each entity's fields apart
+/// compared ignoring case like halo does
+fn check_unique_name(field: &CustomField, report: &ValidationReport) -> Result<(), String> {
+    let name = field.name.to_string();
+    match report.fields.iter().find(|earlier| {
+        earlier.settings.usage == field.settings.usage
+            && earlier.name.to_string().eq_ignore_ascii_case(&name)
+    }) {
+        Some(earlier) => Err(format!(
+            "'{}' is already the name of another {} field ('{}')",
+            name, field.settings.usage, earlier.label
+        )),
+        None => Ok(()),
+    }
+}
+
+/// warns about options halo cannot store as written
+/// rather than letting them change silently on the way
+fn warn_lossy_options(
+    field: &CustomField,
+    report: &mut ValidationReport,
+    location: impl Fn() -> IssueLocation,
+) {
+    if field.field_type.option_tree().is_some() {
+        report.add_warning(
+            location(),
+            Some(SELECTION_OPTIONS_COLUMN),
+            "nested tree options cannot be created yet, the field is only compared with one already in halo"
+                .to_string(),
+        );
+    }
+    for option in field.field_type.selection_options().unwrap_or_default() {
+        let stored = field.field_type.stored_option(&option);
+        if stored != option {
+            report.add_warning(
+                location(),
+                Some(SELECTION_OPTIONS_COLUMN),
+                format!(
+                    "option '{}' contains a comma, which halo cannot store, it will be created as '{}'",
+                    option, stored
+                ),
+            );
+        }
+    }
+}
+
+/// the source column holding the value that failed validation
+fn invalid_field_column(error: &InvalidCustomField) -> &'static str {
+    match error {
+        InvalidCustomField::Name(_) => NAME_COLUMN,
+        InvalidCustomField::Label(_) => LABEL_COLUMN,
+        InvalidCustomField::FieldType(
+            InvalidFieldType::InvalidFieldTypeId | InvalidFieldType::UnknownFieldType(_),
+        ) => FIELD_TYPE_ID_COLUMN,
+        InvalidCustomField::FieldType(
+            InvalidFieldType::InvalidInputType(_) | InvalidFieldType::UnknownInputType { .. },
+        ) => INPUT_TYPE_ID_COLUMN,
+        InvalidCustomField::FieldType(
+            InvalidFieldType::NoOptions
+            | InvalidFieldType::EmptyOption { .. }
+            | InvalidFieldType::OptionTooLong { .. }
+            | InvalidFieldType::DuplicateOption { .. }
+            | InvalidFieldType::DuplicateStoredOption { .. }
+            | InvalidFieldType::NestedOptions,
+        ) => SELECTION_OPTIONS_COLUMN,
+        InvalidCustomField::Settings(
+            InvalidFieldSettings::Usage | InvalidFieldSettings::UnknownUsage(_),
+        ) => USAGE_COLUMN,
+        InvalidCustomField::Settings(InvalidFieldSettings::SearchablePassword { setting }) => {
+            setting
+        }
+        InvalidCustomField::Settings(InvalidFieldSettings::CopyOnUpdateWithoutCopy) => {
+            COPY_TO_CHILD_ON_UPDATE_COLUMN
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(headers: &[&str]) -> FieldPositions {
+        get_field_positions(headers, IssueLocation::Row(1), &mut ValidationReport::new())
+            .expect("required columns present")
+    }
+
+    #[test]
+    fn finds_each_settings_column_by_name() {
+        let positions = positions(&[
+            NAME_COLUMN,
+            LABEL_COLUMN,
+            FIELD_TYPE_ID_COLUMN,
+            INPUT_TYPE_ID_COLUMN,
+            SELECTION_OPTIONS_COLUMN,
+            COPY_TO_CHILD_COLUMN,
+            COPY_TO_CHILD_ON_UPDATE_COLUMN,
+        ]);
+
+        assert_eq!(positions.of(COPY_TO_CHILD_COLUMN), Some(5));
+        assert_eq!(positions.of(COPY_TO_CHILD_ON_UPDATE_COLUMN), Some(6));
+        assert_eq!(positions.of(SEARCHABLE_COLUMN), None);
+    }
+
+    #[test]
+    fn unknown_column_has_no_position() {
+        let positions = positions(&[
+            NAME_COLUMN,
+            LABEL_COLUMN,
+            FIELD_TYPE_ID_COLUMN,
+            INPUT_TYPE_ID_COLUMN,
+            SELECTION_OPTIONS_COLUMN,
+            COPY_TO_CHILD_ON_UPDATE_COLUMN,
+        ]);
 
-        Ok(fields)
+        assert_eq!(positions.of("notacolumn"), None);
     }
 }
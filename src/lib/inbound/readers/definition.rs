@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+use crate::domain::models::custom_field::CustomField;
+use crate::domain::models::custom_field::field_type::type_reference::TypeReference;
+use crate::domain::models::custom_field::settings::RequestedSettings;
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::{
+    NAME_COLUMN, SELECTION_OPTIONS_COLUMN, check_unique_name, invalid_field_column,
+    warn_lossy_options,
+};
+
+/// one field in a structured document
+/// keys match the csv columns, except options are given as a list
+/// types and usage are given by id or by name
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldDefinition {
+    pub name: String,
+    pub label: String,
+    pub field_type_id: TypeReference,
+    #[serde(default)]
+    pub input_type_id: Option<TypeReference>,
+    #[serde(default)]
+    pub selection_options: Vec<String>,
+    #[serde(default)]
+    pub usage: Option<TypeReference>,
+    #[serde(default)]
+    pub searchable: Option<bool>,
+    #[serde(default)]
+    pub user_searchable: Option<bool>,
+    #[serde(default)]
+    pub calendar_searchable: Option<bool>,
+    #[serde(default)]
+    #[serde(rename = "copytochild")]
+    pub copy_to_child: Option<bool>,
+    #[serde(default)]
+    #[serde(rename = "copytochildonupdate")]
+    pub copy_to_child_on_update: Option<bool>,
+}
+
+impl FieldDefinition {
+    /// validates the same way as a csv row
+    /// recording any issue against the key holding the invalid value
+    pub fn into_field(
+        self,
+        report: &mut ValidationReport,
+        location: impl Fn(&str) -> IssueLocation,
+    ) -> Option<CustomField> {
+        match CustomField::validate(
+            &self.name,
+            &self.label,
+            self.field_type_id,
+            self.input_type_id,
+            self.selection_options,
+            RequestedSettings {
+                usage: self.usage,
+                searchable: self.searchable,
+                user_searchable: self.user_searchable,
+                calendar_searchable: self.calendar_searchable,
+                copy_to_child: self.copy_to_child,
+                copy_to_child_on_update: self.copy_to_child_on_update,
+            },
+        ) {
+            Ok(field) => {
+                if let Err(e) = check_unique_name(&field, report) {
+                    report.add_issue(location(NAME_COLUMN), Some(NAME_COLUMN), e);
+                    return None;
+                }
+                warn_lossy_options(&field, report, || location(SELECTION_OPTIONS_COLUMN));
+                Some(field)
+            }
+            Err(problems) => {
+                for e in problems {
+                    let key = invalid_field_column(&e);
+                    report.add_issue(location(key), Some(key), e);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// the key a deserialization error points at
+/// or none when the entry itself is wrong, e.g. a missing key
+pub fn error_key(path: &str) -> Option<String> {
+    (path != ".").then(|| path.to_string())
+}
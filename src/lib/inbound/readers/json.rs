@@ -0,0 +1,124 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::fs;
+
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::definition::{FieldDefinition, error_key};
+
+/// a json document holding its fields under `fields`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonDocument {
+    fields: Vec<serde_json::Value>,
+}
+
+#[derive(Default)]
+pub struct JsonReader;
+
+impl JsonReader {
+    pub fn new() -> Self {
+        JsonReader
+    }
+
+    /// entries are read one at a time
+    /// so a bad entry is reported by its index and key without hiding the rest
+    pub fn validate(&self, path: &str) -> anyhow::Result<ValidationReport> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("failed to open {}", path))?;
+        self.validate_contents(path, &contents)
+    }
+
+    /// `path` only names the document in errors
+    fn validate_contents(&self, path: &str, contents: &str) -> anyhow::Result<ValidationReport> {
+        let document: JsonDocument = serde_json::from_str(contents)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", path, e))?;
+
+        let mut report = ValidationReport::new();
+        for (index, entry) in document.fields.into_iter().enumerate() {
+            let location = IssueLocation::Entry(index);
+            report.rows_checked += 1;
+
+            let definition: FieldDefinition = match serde_path_to_error::deserialize(entry) {
+                Ok(definition) => definition,
+                Err(e) => {
+                    let key = error_key(&e.path().to_string());
+                    report.add_issue(location, key.as_deref(), e.into_inner());
+                    continue;
+                }
+            };
+
+            if let Some(field) = definition.into_field(&mut report, |_| location.clone()) {
+                report.add_field(field);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(contents: &str) -> ValidationReport {
+        JsonReader::new()
+            .validate_contents("fields.json", contents)
+            .unwrap()
+    }
+
+    #[test]
+    fn reports_a_bad_entry_by_index_and_key_and_keeps_the_rest() {
+        let report = validate(
+            r#"{"fields": [
+                {"name": "CFa", "label": "A", "field_type_id": "text"},
+                {"name": "CFb", "label": "B", "field_type_id": true},
+                {"name": "CFc", "label": "C", "field_type_id": "text"}
+            ]}"#,
+        );
+
+        assert_eq!(report.rows_checked, 3);
+        assert_eq!(report.fields.len(), 2);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].location, IssueLocation::Entry(1));
+        assert_eq!(report.issues[0].column.as_deref(), Some("field_type_id"));
+    }
+
+    #[test]
+    fn reports_every_invalid_value_of_an_entry() {
+        let report = validate(
+            r#"{"fields": [{"name": "bad name!", "label": "", "field_type_id": "nonsense"}]}"#,
+        );
+
+        let keys: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.location.clone(), issue.column.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                (IssueLocation::Entry(0), Some("name")),
+                (IssueLocation::Entry(0), Some("label")),
+                (IssueLocation::Entry(0), Some("field_type_id")),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_a_missing_key_against_the_entry() {
+        let report = validate(r#"{"fields": [{"name": "CFa", "field_type_id": "text"}]}"#);
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].location, IssueLocation::Entry(0));
+        assert_eq!(report.issues[0].column, None);
+    }
+
+    #[test]
+    fn rejects_a_document_without_a_fields_list() {
+        assert!(
+            JsonReader::new()
+                .validate_contents("fields.json", r#"[{"name": "CFa"}]"#)
+                .is_err()
+        );
+    }
+}
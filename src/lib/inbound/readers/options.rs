@@ -0,0 +1,128 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidOptions {
+    #[error("unterminated quote, escape a literal quote as \\\"")]
+    UnterminatedQuote,
+    #[error("text after the closing quote of option {position}, quote the whole option")]
+    TextAfterQuote { position: usize },
+}
+
+/// splits one cell of selection options on `delimiter`
+/// an option wrapped in double quotes keeps delimiters inside it, e.g. `"Smith, John"`
+/// a backslash only escapes the delimiter, a quote or another backslash, e.g. `Doe\, Jane`
+/// anything else is kept as written, so `C:\Temp` and `12" monitor` stay whole
+pub fn split_options(raw: &str, delimiter: char) -> Result<Vec<String>, InvalidOptions> {
+    let mut options = Vec::new();
+    let mut chars = raw.chars().peekable();
+
+    loop {
+        let mut option = String::new();
+
+        // a quote only counts when it opens the option, ignoring leading spaces
+        let leading: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_whitespace() && *c != delimiter))
+                .collect();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    None => return Err(InvalidOptions::UnterminatedQuote),
+                    Some('"') => break,
+                    Some('\\') => match chars.next_if(|c| *c == '"' || *c == '\\') {
+                        Some(escaped) => option.push(escaped),
+                        None => option.push('\\'),
+                    },
+                    Some(c) => option.push(c),
+                }
+            }
+            while chars
+                .next_if(|c| c.is_whitespace() && *c != delimiter)
+                .is_some()
+            {}
+            if chars.peek().is_some_and(|c| *c != delimiter) {
+                return Err(InvalidOptions::TextAfterQuote {
+                    position: options.len() + 1,
+                });
+            }
+        } else {
+            option.push_str(&leading);
+            while let Some(c) = chars.next_if(|c| *c != delimiter) {
+                match c {
+                    '\\' => match chars.next_if(|c| *c == delimiter || *c == '"' || *c == '\\') {
+                        Some(escaped) => option.push(escaped),
+                        None => option.push('\\'),
+                    },
+                    c => option.push(c),
+                }
+            }
+        }
+
+        options.push(option);
+        if chars.next().is_none() {
+            return Ok(options);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(raw: &str) -> Vec<String> {
+        split_options(raw, ',').unwrap()
+    }
+
+    #[test]
+    fn splits_on_the_delimiter() {
+        assert_eq!(split("small,medium, large"), ["small", "medium", " large"]);
+        assert_eq!(split_options("a|b,c", '|').unwrap(), ["a", "b,c"]);
+    }
+
+    #[test]
+    fn keeps_empty_options_for_validation_to_report() {
+        assert_eq!(split("a,,b,"), ["a", "", "b", ""]);
+    }
+
+    #[test]
+    fn quotes_keep_delimiters_when_they_wrap_the_option() {
+        assert_eq!(split(r#""Smith, John",other"#), ["Smith, John", "other"]);
+        assert_eq!(split(r#"a, "b, c" ,d"#), ["a", "b, c", "d"]);
+    }
+
+    #[test]
+    fn quotes_inside_an_option_are_literal() {
+        assert_eq!(
+            split(r#"12" monitor,24" monitor"#),
+            [r#"12" monitor"#, r#"24" monitor"#]
+        );
+    }
+
+    #[test]
+    fn backslash_escapes_delimiter_quote_and_backslash() {
+        assert_eq!(split(r"Doe\, Jane,other"), ["Doe, Jane", "other"]);
+        assert_eq!(split(r#"\"quoted\",a\\b"#), [r#""quoted""#, r"a\b"]);
+        assert_eq!(split(r#""say \"hi\", bye""#), [r#"say "hi", bye"#]);
+    }
+
+    #[test]
+    fn other_backslashes_are_literal() {
+        assert_eq!(split(r"C:\Temp,N\A"), [r"C:\Temp", r"N\A"]);
+        assert_eq!(split(r"ends with\"), [r"ends with\"]);
+    }
+
+    #[test]
+    fn rejects_an_unterminated_quote() {
+        assert_eq!(
+            split_options(r#""Smith, John,other"#, ','),
+            Err(InvalidOptions::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn rejects_text_after_a_closing_quote() {
+        assert_eq!(
+            split_options(r#"a,"Smith" John,b"#, ','),
+            Err(InvalidOptions::TextAfterQuote { position: 2 })
+        );
+    }
+}
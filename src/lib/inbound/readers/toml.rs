@@ -0,0 +1,180 @@
+use ::toml::{Spanned, Table, Value};
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::definition::{FieldDefinition, error_key};
+
+/// a toml document holding its fields as `[[fields]]` tables
+/// spans are kept for each entry and value to point issues at them
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlDocument {
+    fields: Vec<Spanned<BTreeMap<String, Spanned<Value>>>>,
+}
+
+#[derive(Default)]
+pub struct TomlReader;
+
+impl TomlReader {
+    pub fn new() -> Self {
+        TomlReader
+    }
+
+    /// entries are read one at a time
+    /// so a bad entry is reported by its line and key without hiding the rest
+    pub fn validate(&self, path: &str) -> anyhow::Result<ValidationReport> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("failed to open {}", path))?;
+        self.validate_contents(path, &contents)
+    }
+
+    /// `path` only names the document in errors
+    fn validate_contents(&self, path: &str, contents: &str) -> anyhow::Result<ValidationReport> {
+        let document: TomlDocument = ::toml::from_str(contents)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", path, e))?;
+
+        let mut report = ValidationReport::new();
+        for (index, entry) in document.fields.into_iter().enumerate() {
+            report.rows_checked += 1;
+
+            let entry_span = entry.span();
+            let entry = entry.into_inner();
+            let spans: BTreeMap<String, Range<usize>> = entry
+                .iter()
+                .map(|(key, value)| (key.clone(), value.span()))
+                .collect();
+            let location = |key: Option<&str>| {
+                // nested paths such as `selection_options[1]` point at their key
+                let span = key
+                    .and_then(|key| key.split(['.', '[']).next())
+                    .and_then(|key| spans.get(key))
+                    .unwrap_or(&entry_span);
+                let (line, column) = line_column(contents, span.start);
+                IssueLocation::Line {
+                    entry: index,
+                    line,
+                    column,
+                }
+            };
+
+            let table: Table = entry
+                .into_iter()
+                .map(|(key, value)| (key, value.into_inner()))
+                .collect();
+            let definition: FieldDefinition =
+                match serde_path_to_error::deserialize(Value::Table(table)) {
+                    Ok(definition) => definition,
+                    Err(e) => {
+                        let key = error_key(&e.path().to_string());
+                        report.add_issue(location(key.as_deref()), key.as_deref(), e.into_inner());
+                        continue;
+                    }
+                };
+
+            if let Some(field) = definition.into_field(&mut report, |key| location(Some(key))) {
+                report.add_field(field);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// one based line and column of a byte offset
+fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    let column = before[line_start..].chars().count() + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(contents: &str) -> ValidationReport {
+        TomlReader::new()
+            .validate_contents("fields.toml", contents)
+            .unwrap()
+    }
+
+    #[test]
+    fn points_an_invalid_value_at_its_line_and_column() {
+        let report = validate(
+            r#"[[fields]]
+name = "CFa"
+label = "A"
+field_type_id = "text"
+
+[[fields]]
+name = "CFb"
+label = "B"
+field_type_id = -1
+"#,
+        );
+
+        assert_eq!(report.fields.len(), 1);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.issues[0].location,
+            IssueLocation::Line {
+                entry: 1,
+                line: 9,
+                column: 17
+            }
+        );
+        assert_eq!(report.issues[0].column.as_deref(), Some("field_type_id"));
+    }
+
+    #[test]
+    fn points_a_nested_value_at_its_key() {
+        let report = validate(
+            r#"[[fields]]
+name = "CFa"
+label = "A"
+field_type_id = "single_select"
+selection_options = ["small", 2]
+"#,
+        );
+
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(
+            report.issues[0].location,
+            IssueLocation::Line { line: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn points_a_missing_key_at_the_entry() {
+        let report = validate(
+            r#"[[fields]]
+name = "CFa"
+field_type_id = "text"
+"#,
+        );
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].column, None);
+        assert!(matches!(
+            report.issues[0].location,
+            IssueLocation::Line {
+                entry: 0,
+                line: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn counts_lines_and_columns_from_one() {
+        assert_eq!(line_column("a\nbc", 0), (1, 1));
+        assert_eq!(line_column("a\nbc", 3), (2, 2));
+        assert_eq!(line_column("é\nx", 3), (2, 1));
+    }
+}
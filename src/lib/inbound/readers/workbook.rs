@@ -0,0 +1,239 @@
+use anyhow::Context;
+use calamine::{Data, Range, Reader, open_workbook_auto};
+
+use crate::config::SourceSettings;
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::{get_field_positions, parse_row};
+
+/// reads xlsx, xlsm, xlsb, xls and ods workbooks
+/// laid out like a csv file, with the headers in the first used row of the sheet
+#[derive(Default)]
+pub struct WorkbookReader;
+
+impl WorkbookReader {
+    pub fn new() -> Self {
+        WorkbookReader
+    }
+
+    /// checks every row of the configured sheet, or of the first sheet when none is given
+    pub fn validate(
+        &self,
+        path: &str,
+        settings: &SourceSettings,
+    ) -> anyhow::Result<ValidationReport> {
+        let mut workbook =
+            open_workbook_auto(path).with_context(|| format!("failed to open {}", path))?;
+
+        let sheet_names = workbook.sheet_names();
+        let sheet = match settings.sheet.as_deref() {
+            Some(sheet) if sheet_names.iter().any(|name| name == sheet) => sheet.to_string(),
+            Some(sheet) => anyhow::bail!(
+                "failed to read {}: no sheet named '{}', expected one of: {}",
+                path,
+                sheet,
+                sheet_names.join(", ")
+            ),
+            None => sheet_names
+                .first()
+                .cloned()
+                .with_context(|| format!("failed to read {}: the workbook has no sheets", path))?,
+        };
+        let range = workbook
+            .worksheet_range(&sheet)
+            .map_err(|e| anyhow::anyhow!("failed to read sheet '{}' of {}: {}", sheet, path, e))?;
+
+        Ok(self.validate_range(sheet, &range, settings))
+    }
+
+    /// checks every row of one sheet
+    fn validate_range(
+        &self,
+        sheet: String,
+        range: &Range<Data>,
+        settings: &SourceSettings,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        // the range starts at the first used cell, which need not be A1
+        let Some((first_row, first_column)) = range.start() else {
+            report.add_issue(
+                IssueLocation::Cell {
+                    sheet,
+                    row: 1,
+                    column: None,
+                },
+                None,
+                "the sheet is empty",
+            );
+            return report;
+        };
+        let location = |row_offset: usize, position: Option<usize>| IssueLocation::Cell {
+            sheet: sheet.clone(),
+            row: first_row as usize + row_offset + 1,
+            column: position.map(|position| first_column as usize + position),
+        };
+
+        let mut rows = range.rows().enumerate();
+        let Some((_, header_cells)) = rows.next() else {
+            return report;
+        };
+        let headers: Vec<String> = header_cells.iter().map(Data::to_string).collect();
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        let Some(positions) = get_field_positions(&headers, location(0, None), &mut report) else {
+            return report;
+        };
+
+        for (row_offset, cells) in rows {
+            if cells.iter().all(|cell| *cell == Data::Empty) {
+                continue;
+            }
+            report.rows_checked += 1;
+
+            let mut readable = true;
+            for (position, cell) in cells.iter().enumerate() {
+                if let Data::Error(e) = cell {
+                    report.add_issue(
+                        location(row_offset, Some(position)),
+                        headers.get(position).copied(),
+                        format!("cell holds an error: {}", e),
+                    );
+                    readable = false;
+                }
+            }
+            if !readable {
+                continue;
+            }
+
+            let row: Vec<String> = cells.iter().map(Data::to_string).collect();
+            let row: Vec<&str> = row.iter().map(String::as_str).collect();
+            let cell_location = |position| location(row_offset, position);
+            let field = parse_row(
+                &row,
+                &positions,
+                settings.option_delimiter,
+                &mut report,
+                cell_location,
+            );
+            if let Some(field) = field {
+                report.add_field(field);
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADERS: [&str; 5] = [
+        "name",
+        "label",
+        "field_type_id",
+        "input_type_id",
+        "selection_options",
+    ];
+
+    /// a sheet whose first used cell is `start`, one row per slice
+    fn sheet(start: (u32, u32), rows: &[&[Data]]) -> Range<Data> {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(1) as u32;
+        let end = (start.0 + rows.len() as u32 - 1, start.1 + width - 1);
+        let mut range = Range::new(start, end);
+        for (row, cells) in rows.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                range.set_value(
+                    (start.0 + row as u32, start.1 + column as u32),
+                    cell.clone(),
+                );
+            }
+        }
+        range
+    }
+
+    fn text(values: &[&str]) -> Vec<Data> {
+        values
+            .iter()
+            .map(|value| Data::String(value.to_string()))
+            .collect()
+    }
+
+    fn validate(range: &Range<Data>) -> ValidationReport {
+        WorkbookReader::new().validate_range(
+            "fields".to_string(),
+            range,
+            &SourceSettings::default(),
+        )
+    }
+
+    #[test]
+    fn locates_cells_from_where_the_sheet_starts() {
+        let headers = text(&HEADERS);
+        let valid = text(&["CFa", "A", "text", "", ""]);
+        let invalid = text(&["CFb", "B", "nonsense", "", ""]);
+        // headers in C3, so the invalid type is in cell E5
+        let range = sheet((2, 2), &[&headers, &valid, &invalid]);
+
+        let report = validate(&range);
+
+        assert_eq!(report.rows_checked, 2);
+        assert_eq!(report.fields.len(), 1);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.issues[0].location,
+            IssueLocation::Cell {
+                sheet: "fields".to_string(),
+                row: 5,
+                column: Some(4),
+            }
+        );
+        assert!(report.issues[0].to_string().contains("cell E5"));
+    }
+
+    #[test]
+    fn reports_a_cell_holding_an_error() {
+        let headers = text(&HEADERS);
+        let mut row = text(&["CFa", "A", "text", "", ""]);
+        row[1] = Data::Error(calamine::CellErrorType::Ref);
+        let range = sheet((0, 0), &[&headers, &row]);
+
+        let report = validate(&range);
+
+        assert_eq!(report.fields.len(), 0);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.issues[0].location,
+            IssueLocation::Cell {
+                sheet: "fields".to_string(),
+                row: 2,
+                column: Some(1),
+            }
+        );
+        assert_eq!(report.issues[0].column.as_deref(), Some("label"));
+    }
+
+    #[test]
+    fn skips_empty_rows_and_reports_missing_columns_on_the_header_row() {
+        let headers = text(&["name", "label"]);
+        let empty = vec![Data::Empty, Data::Empty];
+        let range = sheet((1, 0), &[&headers, &empty]);
+
+        let report = validate(&range);
+
+        assert_eq!(report.rows_checked, 0);
+        assert!(report.issues.iter().all(|issue| issue.location
+            == IssueLocation::Cell {
+                sheet: "fields".to_string(),
+                row: 2,
+                column: None,
+            }));
+        assert_eq!(report.issues.len(), 3);
+    }
+
+    #[test]
+    fn reports_an_empty_sheet() {
+        let report = validate(&Range::empty());
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].message, "the sheet is empty");
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::Context;
+use marked_yaml::{Marker, Node};
+use std::fs;
+
+use crate::domain::validation_report::{IssueLocation, ValidationReport};
+use crate::inbound::readers::definition::{FieldDefinition, error_key};
+
+const FIELDS_KEY: &str = "fields";
+
+#[derive(Default)]
+pub struct YamlReader;
+
+impl YamlReader {
+    pub fn new() -> Self {
+        YamlReader
+    }
+
+    /// entries are read one at a time from the parsed nodes
+    /// which keep the line and column each value was written at
+    pub fn validate(&self, path: &str) -> anyhow::Result<ValidationReport> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("failed to open {}", path))?;
+        self.validate_contents(path, &contents)
+    }
+
+    /// `path` only names the document in errors
+    fn validate_contents(&self, path: &str, contents: &str) -> anyhow::Result<ValidationReport> {
+        let document = marked_yaml::parse_yaml(0, contents)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", path, e))?;
+
+        let mapping = document
+            .as_mapping()
+            .with_context(|| format!("failed to parse {}: expected a mapping", path))?;
+        if let Some(key) = mapping.keys().find(|key| key.as_str() != FIELDS_KEY) {
+            anyhow::bail!(
+                "failed to parse {}: unknown key `{}` at {}, expected `{}`",
+                path,
+                key.as_str(),
+                position(key.span().start()),
+                FIELDS_KEY
+            );
+        }
+        let fields = mapping.get_sequence(FIELDS_KEY).with_context(|| {
+            format!("failed to parse {}: expected a `{}` list", path, FIELDS_KEY)
+        })?;
+
+        let mut report = ValidationReport::new();
+        for (index, entry) in fields.iter().enumerate() {
+            report.rows_checked += 1;
+
+            let definition: FieldDefinition = match marked_yaml::from_node(entry) {
+                Ok(definition) => definition,
+                Err(e) => {
+                    let key = e.path().and_then(error_key);
+                    let marker = e.start_mark().or_else(|| entry.span().start().copied());
+                    report.add_issue(
+                        location(index, marker.as_ref()),
+                        key.as_deref(),
+                        e.into_inner(),
+                    );
+                    continue;
+                }
+            };
+
+            let field = definition.into_field(&mut report, |key| {
+                location(index, value_node(entry, key).span().start())
+            });
+            if let Some(field) = field {
+                report.add_field(field);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// the node holding `key`, or the entry itself when the key is missing
+fn value_node<'a>(entry: &'a Node, key: &str) -> &'a Node {
+    entry
+        .as_mapping()
+        .and_then(|mapping| mapping.get_node(key))
+        .unwrap_or(entry)
+}
+
+fn location(entry: usize, marker: Option<&Marker>) -> IssueLocation {
+    IssueLocation::Line {
+        entry,
+        line: marker.map_or(0, Marker::line),
+        column: marker.map_or(0, Marker::column),
+    }
+}
+
+fn position(marker: Option<&Marker>) -> String {
+    match marker {
+        Some(marker) => format!("line {}, column {}", marker.line(), marker.column()),
+        None => "an unknown position".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validation_report::ValidationIssue;
+
+    fn validate(contents: &str) -> ValidationReport {
+        YamlReader::new()
+            .validate_contents("fields.yaml", contents)
+            .unwrap()
+    }
+
+    fn line_of(issue: &ValidationIssue) -> (usize, usize) {
+        match issue.location {
+            IssueLocation::Line { line, column, .. } => (line, column),
+            ref other => panic!("expected a line location, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn points_an_invalid_value_at_its_line_and_column() {
+        let report = validate(
+            "fields:
+  - name: CFa
+    label: A
+    field_type_id: text
+  - name: CFb
+    label: B
+    field_type_id: nonsense
+",
+        );
+
+        assert_eq!(report.fields.len(), 1);
+        assert_eq!(report.issues.len(), 1);
+        let issue = &report.issues[0];
+        assert!(matches!(
+            issue.location,
+            IssueLocation::Line { entry: 1, .. }
+        ));
+        assert_eq!(issue.column.as_deref(), Some("field_type_id"));
+        assert_eq!(line_of(issue), (7, 20));
+    }
+
+    #[test]
+    fn points_a_value_of_the_wrong_shape_at_its_line() {
+        let report = validate(
+            "fields:
+  - name: CFa
+    label: A
+    field_type_id: text
+    searchable: sometimes
+",
+        );
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].column.as_deref(), Some("searchable"));
+        assert_eq!(line_of(&report.issues[0]).0, 5);
+    }
+
+    #[test]
+    fn points_a_missing_key_at_the_entry() {
+        let report = validate(
+            "fields:
+  - name: CFa
+    field_type_id: text
+",
+        );
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].column, None);
+        assert_eq!(line_of(&report.issues[0]).0, 2);
+    }
+
+    #[test]
+    fn rejects_an_unknown_top_level_key() {
+        let error = YamlReader::new()
+            .validate_contents("fields.yaml", "fields: []\nfeilds: []\n")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("unknown key `feilds` at line 2"));
+    }
+}
@@ -1,10 +1,19 @@
 use colored::*;
 use log::{error, info};
-use std::io::{self, Write};
-
-use crate::domain::import_result::ImportResults;
+use std::io;
+
+use crate::config::profile::ActiveProfile;
+use crate::domain::collision::{ConflictPolicy, FieldStatus};
+use crate::domain::deletion::{DeletionPlan, DeletionTarget, Removal};
+use crate::domain::diff::{self, FieldChange};
+use crate::domain::import_result::{FieldAction, ImportResults};
+use crate::domain::journal::Journal;
+use crate::domain::logging;
 use crate::domain::models::custom_field::CustomField;
+use crate::domain::models::existing_field::ExistingField;
+use crate::domain::plan::{Plan, PlannedAction};
 use crate::outbound::field_client::FieldClient;
+use crate::{hprint, hprintln};
 
 #[derive(Debug)]
 pub enum RunMode {
@@ -22,73 +31,552 @@ pub enum DebugAction {
 
 pub struct ScreenManager {
     fields: Vec<CustomField>,
+    existing_fields: Vec<ExistingField>,
+    conflict_policy: ConflictPolicy,
 }
 
 impl ScreenManager {
-    pub fn new(fields: Vec<CustomField>) -> Self {
-        Self { fields }
+    /// `existing_fields` is empty when the instance was not checked
+    /// fields are grouped by entity, keeping the source file order within each
+    pub fn new(
+        mut fields: Vec<CustomField>,
+        existing_fields: Vec<ExistingField>,
+        conflict_policy: ConflictPolicy,
+    ) -> Self {
+        fields.sort_by_key(|field| field.settings.usage);
+        Self {
+            fields,
+            existing_fields,
+            conflict_policy,
+        }
     }
 
-    pub fn show_initial_stats(&self, token_type: &str) -> anyhow::Result<()> {
-        println!("\n{}", "initial status:".bright_blue().bold());
-        println!("{}", "=".repeat(80).bright_blue());
+    fn field_status(&self, field: &CustomField) -> FieldStatus {
+        FieldStatus::of(field, &self.existing_fields)
+    }
 
-        println!(
-            "• authentication: {} (token type: {})",
-            "success".bright_green().bold(),
-            token_type.bright_yellow()
-        );
+    /// `profile` is none when settings came from the environment alone
+    /// `token_type` is none when a dry run skipped authentication
+    pub fn show_initial_stats(
+        &self,
+        profile: Option<&ActiveProfile>,
+        token_type: Option<&str>,
+        dry_run: bool,
+    ) -> anyhow::Result<()> {
+        hprintln!("\n{}", "initial status:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
+
+        match profile {
+            Some(profile) if profile.protected => hprintln!(
+                "• profile: {} {}",
+                profile.name.to_uppercase().bright_red().bold(),
+                "(protected)".bright_red()
+            ),
+            Some(profile) => hprintln!("• profile: {}", profile.name.bright_green().bold()),
+            None => hprintln!("• profile: {}", "none (environment)".bright_yellow()),
+        }
+
+        match token_type {
+            Some(token_type) => hprintln!(
+                "• authentication: {} (token type: {})",
+                "success".bright_green().bold(),
+                token_type.bright_yellow()
+            ),
+            None => hprintln!(
+                "• authentication: {} (no credentials configured)",
+                "skipped".bright_yellow().bold()
+            ),
+        }
+
+        if dry_run {
+            hprintln!(
+                "• mode: {}",
+                "dry run (requests are recorded, not sent)"
+                    .bright_cyan()
+                    .bold()
+            );
+        }
 
-        println!(
+        hprintln!(
             "• fields loaded: {}",
             self.fields.len().to_string().bright_yellow()
         );
+        for fields in self
+            .fields
+            .chunk_by(|a, b| a.settings.usage == b.settings.usage)
+        {
+            hprintln!(
+                "  {}: {}",
+                fields[0].settings.usage,
+                fields.len().to_string().bright_yellow()
+            );
+        }
 
-        println!("• status: {}", "ready to process".bright_green().bold());
+        hprintln!("• status: {}", "ready to process".bright_green().bold());
 
-        println!("{}\n", "=".repeat(80).bright_blue());
+        hprintln!("{}\n", "=".repeat(80).bright_blue());
         Ok(())
     }
 
+    pub fn show_collision_report(&self) {
+        let statuses: Vec<FieldStatus> = self
+            .fields
+            .iter()
+            .map(|field| self.field_status(field))
+            .collect();
+        let count =
+            |matches: fn(&FieldStatus) -> bool| statuses.iter().filter(|s| matches(s)).count();
+
+        hprintln!("\n{}", "existing field check:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
+
+        hprintln!(
+            "• fields on instance: {}",
+            self.existing_fields.len().to_string().bright_yellow()
+        );
+        hprintln!(
+            "• new: {}",
+            count(|s| matches!(s, FieldStatus::New))
+                .to_string()
+                .bright_green()
+        );
+        hprintln!(
+            "• identical: {}",
+            count(|s| matches!(s, FieldStatus::Identical { .. }))
+                .to_string()
+                .bright_cyan()
+        );
+        hprintln!(
+            "• conflicting: {}",
+            count(|s| matches!(s, FieldStatus::Conflicting { .. }))
+                .to_string()
+                .bright_red()
+        );
+
+        for (field, status) in self.fields.iter().zip(&statuses) {
+            if let FieldStatus::Conflicting { .. } = status {
+                hprintln!(
+                    "  {} {} ({})",
+                    "!".bright_red().bold(),
+                    field.name.to_string().bright_yellow(),
+                    status.to_string().bright_red()
+                );
+            }
+        }
+
+        hprintln!("{}\n", "=".repeat(80).bright_blue());
+    }
+
+    pub fn plan(&self) -> Plan {
+        Plan::new(&self.fields, &self.existing_fields)
+    }
+
+    pub fn show_plan(&self, plan: &Plan) {
+        hprintln!("\n{}", "planned changes:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
+
+        let mut current_usage = None;
+        for action in &plan.actions {
+            if matches!(action, PlannedAction::Unchanged { .. }) {
+                continue;
+            }
+            if current_usage != Some(action.usage()) {
+                current_usage = Some(action.usage());
+                hprintln!("{}", format!("{} fields:", action.usage()).bright_blue());
+            }
+
+            match action {
+                PlannedAction::Create(field) => hprintln!(
+                    "{} {} {} ({}, type id: {})",
+                    "+".bright_green().bold(),
+                    "create".bright_green(),
+                    field.label.to_string().bright_yellow(),
+                    field.name,
+                    field.field_type.field_type_id()
+                ),
+                PlannedAction::Update { id, field, changes } => {
+                    hprintln!(
+                        "{} {} {} ({}, id: {})",
+                        "~".bright_cyan().bold(),
+                        "update".bright_cyan(),
+                        field.label.to_string().bright_yellow(),
+                        field.name,
+                        id
+                    );
+                    for change in changes {
+                        self.show_field_change(change);
+                    }
+                }
+                PlannedAction::Unchanged { .. } => {}
+                PlannedAction::Conflict { id, field, reason } => hprintln!(
+                    "{} {} {} ({}, id: {}): {}",
+                    "!".bright_red().bold(),
+                    "conflict".bright_red(),
+                    field.label.to_string().bright_yellow(),
+                    field.name,
+                    id,
+                    reason.bright_red()
+                ),
+                PlannedAction::Orphan(existing) => hprintln!(
+                    "{} {} {} (id: {}, not in source file, left untouched)",
+                    "-".bright_magenta().bold(),
+                    "orphan".bright_magenta(),
+                    existing.name.bright_yellow(),
+                    existing.id
+                ),
+            }
+        }
+
+        hprintln!(
+            "\n• to create: {}",
+            plan.creates().to_string().bright_green()
+        );
+        hprintln!("• to update: {}", plan.updates().to_string().bright_cyan());
+        hprintln!(
+            "• unchanged: {}",
+            plan.unchanged().to_string().bright_yellow()
+        );
+        hprintln!("• conflicts: {}", plan.conflicts().to_string().bright_red());
+        hprintln!("• orphans: {}", plan.orphans().to_string().bright_magenta());
+        hprintln!("{}\n", "=".repeat(80).bright_blue());
+    }
+
+    fn show_field_change(&self, change: &FieldChange) {
+        match change {
+            FieldChange::Label { from, to } => hprintln!(
+                "    label: {} → {}",
+                from.to_string().bright_red(),
+                to.to_string().bright_green()
+            ),
+            FieldChange::InputType { from, to } => hprintln!(
+                "    input type id: {} → {}",
+                from.map_or("none".to_string(), |id| id.to_string())
+                    .bright_red(),
+                to.map_or("none".to_string(), |id| id.to_string())
+                    .bright_green()
+            ),
+            FieldChange::OptionsAdded(options) => {
+                for option in options {
+                    hprintln!(
+                        "    {} option: {}",
+                        "+".bright_green(),
+                        option.bright_green()
+                    );
+                }
+            }
+            FieldChange::OptionsRemoved(options) => {
+                for option in options {
+                    hprintln!(
+                        "    {} option: {} {}",
+                        "-".bright_red(),
+                        option.bright_red(),
+                        "(left in place)".dimmed()
+                    );
+                }
+            }
+            FieldChange::Setting { name, from, to } => hprintln!(
+                "    {}: {} → {} {}",
+                name,
+                from.to_string().bright_red(),
+                to.to_string().bright_green(),
+                "(not changed by updates)".dimmed()
+            ),
+        }
+    }
+
+    /// asks before applying unless the caller already confirmed
+    pub fn confirm_apply(&self, plan: &Plan) -> anyhow::Result<bool> {
+        self.confirm(&format!(
+            "apply {} create(s) and {} update(s)?",
+            plan.creates(),
+            plan.updates()
+        ))
+    }
+
+    /// protected profiles need their name typed rather than 'yes'
+    pub fn confirm_protected_profile(&self, profile: &ActiveProfile) -> anyhow::Result<bool> {
+        hprint!(
+            "\n{}",
+            format!(
+                "profile '{}' is protected. type its name to write to it: ",
+                profile.name
+            )
+            .bright_red()
+            .bold()
+        );
+        logging::flush_human()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim() == profile.name)
+    }
+
+    /// only an explicit 'yes' confirms
+    fn confirm(&self, question: &str) -> anyhow::Result<bool> {
+        hprint!(
+            "\n{}",
+            format!("{} type 'yes' to continue: ", question)
+                .bright_white()
+                .bold()
+        );
+        logging::flush_human()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim() == "yes")
+    }
+
+    pub fn show_deletion_plan(&self, plan: &DeletionPlan) {
+        hprintln!("\n{}", "fields to remove:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
+
+        for target in &plan.targets {
+            match target {
+                DeletionTarget::Delete(existing) => hprintln!(
+                    "{} {} {} ({}, {}, id: {})",
+                    "-".bright_red().bold(),
+                    "delete".bright_red(),
+                    existing.label().bright_yellow(),
+                    existing.name,
+                    existing.usage,
+                    existing.id
+                ),
+                DeletionTarget::Archive { existing, field } => hprintln!(
+                    "{} {} {} ({}, {}, id: {}) → {}",
+                    "~".bright_cyan().bold(),
+                    "archive".bright_cyan(),
+                    existing.label().bright_yellow(),
+                    existing.name,
+                    existing.usage,
+                    existing.id,
+                    field.label.to_string().bright_green()
+                ),
+                DeletionTarget::NotFound(entry) => hprintln!(
+                    "{} {} {} ({}, not on instance)",
+                    "?".bright_cyan().bold(),
+                    "missing".bright_cyan(),
+                    entry.name.bright_yellow(),
+                    entry.usage
+                ),
+                DeletionTarget::Refused { existing, reason } => hprintln!(
+                    "{} {} {} ({}, {}, id: {}): {}",
+                    "!".bright_magenta().bold(),
+                    "refused".bright_magenta(),
+                    existing.label().bright_yellow(),
+                    existing.name,
+                    existing.usage,
+                    existing.id,
+                    reason.bright_magenta()
+                ),
+            }
+        }
+
+        let verb = match plan.removal {
+            Removal::Delete => "delete",
+            Removal::Archive => "archive",
+        };
+        hprintln!(
+            "\n• to {}: {}",
+            verb,
+            plan.deletions().to_string().bright_red()
+        );
+        hprintln!("{}\n", "=".repeat(80).bright_blue());
+    }
+
+    pub fn confirm_deletion(&self, plan: &DeletionPlan) -> anyhow::Result<bool> {
+        self.confirm(&match plan.removal {
+            Removal::Delete => format!(
+                "permanently delete {} field(s) and any data they hold from halo? this tool does not check for data.",
+                plan.deletions()
+            ),
+            Removal::Archive => format!(
+                "relabel {} field(s) as archived in halo? their data is kept.",
+                plan.deletions()
+            ),
+        })
+    }
+
+    pub async fn delete_fields(
+        &self,
+        plan: &DeletionPlan,
+        field_client: &FieldClient,
+    ) -> anyhow::Result<ImportResults> {
+        let mut results = ImportResults::new();
+        let action = match plan.removal {
+            Removal::Delete => FieldAction::Delete,
+            Removal::Archive => FieldAction::Archive,
+        };
+
+        for target in &plan.targets {
+            match target {
+                DeletionTarget::Delete(existing) => {
+                    match field_client.delete_field(existing.id, &existing.name).await {
+                        Ok(attempts) => {
+                            results.add_success(
+                                &existing.name,
+                                existing.label(),
+                                existing.usage,
+                                FieldAction::Delete,
+                                attempts,
+                            );
+                            info!("✓ field deleted: {}", existing.name);
+                        }
+                        Err(e) => {
+                            results.add_failure(
+                                &existing.name,
+                                existing.label(),
+                                existing.usage,
+                                FieldAction::Delete,
+                                e.attempts,
+                                e.to_string(),
+                            );
+                            error!("✗ field deletion failed: {}", e);
+                        }
+                    }
+                }
+                DeletionTarget::Archive { existing, field } => {
+                    match field_client.update_field(existing.id, field, &[]).await {
+                        Ok(attempts) => {
+                            results.add_success(
+                                &existing.name,
+                                existing.label(),
+                                existing.usage,
+                                FieldAction::Archive,
+                                attempts,
+                            );
+                            info!("✓ field archived: {}", existing.name);
+                        }
+                        Err(e) => {
+                            results.add_failure(
+                                &existing.name,
+                                existing.label(),
+                                existing.usage,
+                                FieldAction::Archive,
+                                e.attempts,
+                                e.to_string(),
+                            );
+                            error!("✗ field archiving failed: {}", e);
+                        }
+                    }
+                }
+                DeletionTarget::NotFound(entry) => {
+                    results.add_skipped(
+                        &entry.name,
+                        &entry.name,
+                        entry.usage,
+                        action,
+                        "not on instance".to_string(),
+                    );
+                }
+                DeletionTarget::Refused { existing, reason } => {
+                    results.add_skipped(
+                        &existing.name,
+                        existing.label(),
+                        existing.usage,
+                        action,
+                        reason.clone(),
+                    );
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub async fn apply_plan(
+        &self,
+        plan: &Plan,
+        field_client: &FieldClient,
+    ) -> anyhow::Result<ImportResults> {
+        let mut results = ImportResults::new();
+
+        for action in &plan.actions {
+            match action {
+                PlannedAction::Create(field) => match field_client.create_field(field).await {
+                    Ok(attempts) => {
+                        results.add_success(
+                            &field.name,
+                            &field.label,
+                            field.settings.usage,
+                            FieldAction::Create,
+                            attempts,
+                        );
+                        info!("✓ field created: {}", field.label);
+                    }
+                    Err(e) => {
+                        results.add_failure(
+                            &field.name,
+                            &field.label,
+                            field.settings.usage,
+                            FieldAction::Create,
+                            e.attempts,
+                            e.to_string(),
+                        );
+                        error!("✗ field creation failed: {}", e);
+                    }
+                },
+                PlannedAction::Update { id, field, changes } => {
+                    self.update_field(*id, field, changes, field_client, &mut results)
+                        .await
+                }
+                PlannedAction::Conflict { field, reason, .. } => {
+                    results.add_skipped(
+                        &field.name,
+                        &field.label,
+                        field.settings.usage,
+                        FieldAction::Update,
+                        reason.clone(),
+                    );
+                    info!("• field skipped: {} ({})", field.label, reason);
+                }
+                PlannedAction::Unchanged { .. } | PlannedAction::Orphan(_) => {}
+            }
+        }
+
+        Ok(results)
+    }
+
     pub fn get_run_mode(&self) -> anyhow::Result<RunMode> {
-        println!("{}", "\navailable operations:".bright_blue().bold());
-        println!("{}", "=".repeat(80).bright_blue());
+        hprintln!("{}", "\navailable operations:".bright_blue().bold());
+        hprintln!("{}", "=".repeat(80).bright_blue());
 
-        println!(
+        hprintln!(
             "{}. {}",
             "1".bright_yellow().bold(),
             "import all fields".bright_green()
         );
 
-        println!(
+        hprintln!(
             "{}. {}",
             "2".bright_yellow().bold(),
             "debug mode (field by field)".bright_cyan()
         );
 
-        println!(
+        hprintln!(
             "{}. {}",
             "3".bright_yellow().bold(),
             "quit program".bright_red()
         );
 
-        print!("\n{}", "enter your choice (1-3): ".bright_white().bold());
-        io::stdout().flush()?;
+        hprint!("\n{}", "enter your choice (1-3): ".bright_white().bold());
+        logging::flush_human()?;
 
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
 
         match input.trim() {
             "1" => {
-                println!("\n{}", "selected: import all fields".bright_green());
+                hprintln!("\n{}", "selected: import all fields".bright_green());
                 Ok(RunMode::Import)
             }
             "2" => {
-                println!("\n{}", "selected: debug mode".bright_cyan());
+                hprintln!("\n{}", "selected: debug mode".bright_cyan());
                 Ok(RunMode::Debug)
             }
             "3" => {
-                println!("\n{}", "selected: quit program".bright_red());
+                hprintln!("\n{}", "selected: quit program".bright_red());
                 Ok(RunMode::Quit)
             }
             _ => {
@@ -98,29 +586,205 @@ impl ScreenManager {
         }
     }
 
+    /// `journal` is none for dry runs, which change nothing worth resuming
     pub async fn process_all_fields(
         &self,
         field_client: &FieldClient,
+        journal: Option<&Journal>,
     ) -> anyhow::Result<ImportResults> {
         let mut results = ImportResults::new();
 
         for field in &self.fields {
-            match field_client.create_field(field).await {
-                Ok(_) => {
-                    results.add_success(field.label.to_string());
+            self.process_journaled(field, field_client, &mut results, journal)
+                .await?;
+        }
+
+        Ok(results)
+    }
+
+    /// skips fields an interrupted run already created
+    /// and journals the attempt and outcome of every other field
+    async fn process_journaled(
+        &self,
+        field: &CustomField,
+        field_client: &FieldClient,
+        results: &mut ImportResults,
+        journal: Option<&Journal>,
+    ) -> anyhow::Result<()> {
+        let Some(journal) = journal else {
+            self.process_field(field, field_client, results).await;
+            return Ok(());
+        };
+
+        if journal.already_created(field) {
+            results.add_skipped(
+                &field.name,
+                &field.label,
+                field.settings.usage,
+                FieldAction::Create,
+                "created by an earlier run".to_string(),
+            );
+            info!(
+                "• field skipped: {} (created by an earlier run)",
+                field.label
+            );
+            return Ok(());
+        }
+
+        journal.record_attempt(field)?;
+        self.process_field(field, field_client, results).await;
+        if let Some(result) = results.latest_for(&field.name.to_string()) {
+            journal.record_outcome(result)?;
+        }
+
+        Ok(())
+    }
+
+    /// creates new fields and applies the conflict policy to existing names
+    async fn process_field(
+        &self,
+        field: &CustomField,
+        field_client: &FieldClient,
+        results: &mut ImportResults,
+    ) {
+        let status = self.field_status(field);
+        match (&status, self.conflict_policy) {
+            (FieldStatus::New, _) => match field_client.create_field(field).await {
+                Ok(attempts) => {
+                    results.add_success(
+                        &field.name,
+                        &field.label,
+                        field.settings.usage,
+                        FieldAction::Create,
+                        attempts,
+                    );
                     info!("✓ field processed successfully: {}", field.label);
                 }
                 Err(e) => {
-                    results.add_failure(field.label.to_string(), e.to_string());
+                    results.add_failure(
+                        &field.name,
+                        &field.label,
+                        field.settings.usage,
+                        FieldAction::Create,
+                        e.attempts,
+                        e.to_string(),
+                    );
                     error!("✗ field processing failed: {}", e);
                 }
+            },
+            (FieldStatus::Identical { .. }, _)
+            | (FieldStatus::Conflicting { .. }, ConflictPolicy::Skip) => {
+                results.add_skipped(
+                    &field.name,
+                    &field.label,
+                    field.settings.usage,
+                    FieldAction::Create,
+                    status.to_string(),
+                );
+                info!("• field skipped: {} ({})", field.label, status);
+            }
+            (FieldStatus::Conflicting { .. }, ConflictPolicy::Flag) => {
+                results.add_failure(
+                    &field.name,
+                    &field.label,
+                    field.settings.usage,
+                    FieldAction::Create,
+                    0,
+                    status.to_string(),
+                );
+                error!(
+                    "✗ field conflicts with existing field: {} ({})",
+                    field.label, status
+                );
+            }
+            (FieldStatus::Conflicting { .. }, ConflictPolicy::Update) => {
+                match PlannedAction::of(field, &self.existing_fields) {
+                    PlannedAction::Update { id, field, changes } => {
+                        self.update_field(id, &field, &changes, field_client, results)
+                            .await
+                    }
+                    PlannedAction::Conflict { reason, .. } => {
+                        results.add_failure(
+                            &field.name,
+                            &field.label,
+                            field.settings.usage,
+                            FieldAction::Update,
+                            0,
+                            reason.clone(),
+                        );
+                        error!("✗ field cannot be updated: {} ({})", field.label, reason);
+                    }
+                    _ => {
+                        results.add_skipped(
+                            &field.name,
+                            &field.label,
+                            field.settings.usage,
+                            FieldAction::Update,
+                            "no changes to apply".to_string(),
+                        );
+                        info!("• field skipped: {} (no changes to apply)", field.label);
+                    }
+                }
             }
         }
+    }
 
-        Ok(results)
+    /// applies label, input type and added options
+    /// removed options and changed settings are left in place
+    async fn update_field(
+        &self,
+        id: i64,
+        field: &CustomField,
+        changes: &[FieldChange],
+        field_client: &FieldClient,
+        results: &mut ImportResults,
+    ) {
+        if !changes.iter().any(FieldChange::is_applied) {
+            results.add_skipped(
+                &field.name,
+                &field.label,
+                field.settings.usage,
+                FieldAction::Update,
+                "only removes options or changes settings, which are left in place".to_string(),
+            );
+            info!(
+                "• field update skipped: {} (only removes options or changes settings)",
+                field.label
+            );
+            return;
+        }
+
+        let added_options = diff::added_options(changes);
+        match field_client.update_field(id, field, &added_options).await {
+            Ok(attempts) => {
+                results.add_success(
+                    &field.name,
+                    &field.label,
+                    field.settings.usage,
+                    FieldAction::Update,
+                    attempts,
+                );
+                info!("✓ field updated: {}", field.label);
+            }
+            Err(e) => {
+                results.add_failure(
+                    &field.name,
+                    &field.label,
+                    field.settings.usage,
+                    FieldAction::Update,
+                    e.attempts,
+                    e.to_string(),
+                );
+                error!("✗ field update failed: {}", e);
+            }
+        }
     }
 
-    pub async fn debug_mode(&self, field_client: &FieldClient) -> anyhow::Result<ImportResults> {
+    pub async fn debug_mode(
+        &self,
+        field_client: &FieldClient,
+        journal: Option<&Journal>,
+    ) -> anyhow::Result<ImportResults> {
         info!("\nentering debug mode");
         info!("this mode will process fields one at a time\n");
 
@@ -130,17 +794,8 @@ impl ScreenManager {
             match self.show_field_debug_prompt(index, field)? {
                 DebugAction::Process => {
                     info!("processing field: {}", field.label);
-
-                    match field_client.create_field(field).await {
-                        Ok(_) => {
-                            results.add_success(field.label.to_string());
-                            info!("✓ field processed successfully\n");
-                        }
-                        Err(e) => {
-                            results.add_failure(field.label.to_string(), e.to_string());
-                            error!("✗ field processing failed: {}\n", e);
-                        }
-                    }
+                    self.process_journaled(field, field_client, &mut results, journal)
+                        .await?;
                 }
                 DebugAction::Skip => {
                     info!("skipping field: {}\n", field.label);
@@ -161,8 +816,8 @@ impl ScreenManager {
         index: usize,
         field: &CustomField,
     ) -> anyhow::Result<DebugAction> {
-        println!("\n{}", "=".repeat(80).bright_blue());
-        println!(
+        hprintln!("\n{}", "=".repeat(80).bright_blue());
+        hprintln!(
             "{}",
             format!(
                 "field {} of {}",
@@ -172,46 +827,72 @@ impl ScreenManager {
             .bright_blue()
             .bold()
         );
-        println!("{}", "=".repeat(80).bright_blue());
+        hprintln!("{}", "=".repeat(80).bright_blue());
 
-        println!("\n{}", "field details:".bright_blue().bold());
-        println!("• label: {}", field.label.to_string().bright_yellow());
-        println!("• name: {}", field.name.to_string().bright_yellow());
-        println!(
+        hprintln!("\n{}", "field details:".bright_blue().bold());
+        hprintln!("• label: {}", field.label.to_string().bright_yellow());
+        hprintln!("• name: {}", field.name.to_string().bright_yellow());
+        hprintln!(
             "• type id: {}",
             field.field_type.field_type_id().to_string().bright_yellow()
         );
 
         if let Some(input_type_id) = field.field_type.input_type_id() {
-            println!(
+            hprintln!(
                 "• input type id: {}",
                 input_type_id.to_string().bright_yellow()
             );
         }
 
         if let Some(options) = field.field_type.selection_options_string() {
-            println!("• options: {}", options.bright_yellow());
+            hprintln!("• options: {}", options.bright_yellow());
+        }
+
+        let settings = &field.settings;
+        hprintln!("• usage: {}", settings.usage.to_string().bright_yellow());
+        hprintln!(
+            "• searchable: {}",
+            format!(
+                "{} (users: {}, calendar: {})",
+                settings.searchable, settings.user_searchable, settings.calendar_searchable
+            )
+            .bright_yellow()
+        );
+        hprintln!(
+            "• copy to child: {}",
+            format!(
+                "{} (on update: {})",
+                settings.copy_to_child, settings.copy_to_child_on_update
+            )
+            .bright_yellow()
+        );
+
+        if !self.existing_fields.is_empty() {
+            hprintln!(
+                "• status: {}",
+                self.field_status(field).to_string().bright_yellow()
+            );
         }
 
-        println!("\n{}", "available actions:".bright_blue().bold());
-        println!(
+        hprintln!("\n{}", "available actions:".bright_blue().bold());
+        hprintln!(
             "{}. {} field",
             "1".bright_yellow().bold(),
             "process".bright_green()
         );
-        println!(
+        hprintln!(
             "{}. {} field",
             "2".bright_yellow().bold(),
             "skip".bright_cyan()
         );
-        println!(
+        hprintln!(
             "{}. {} debug mode",
             "3".bright_yellow().bold(),
             "quit".bright_red()
         );
 
-        print!("\n{}", "enter your choice (1-3): ".bright_white().bold());
-        io::stdout().flush()?;
+        hprint!("\n{}", "enter your choice (1-3): ".bright_white().bold());
+        logging::flush_human()?;
 
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
@@ -2,9 +2,12 @@ use reqwest::Client as ReqwestClient;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use url::Url;
 
 use super::token::AuthToken;
 use crate::config::Config;
+use crate::outbound::rate_limiter::RateLimiter;
+use crate::outbound::retry;
 
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
@@ -16,28 +19,43 @@ struct TokenResponse {
 #[derive(Debug, Deserialize)]
 struct ErrorResponse {
     error: String,
+    #[serde(default)]
     error_description: String,
 }
 
+impl ErrorResponse {
+    /// `invalid_scope` is the oauth error for a refused scope
+    /// halo also mentions the scope in other errors when none was requested
+    fn is_scope_error(&self) -> bool {
+        self.error == "invalid_scope" || self.error_description.to_lowercase().contains("scope")
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct TokenRequest {
     client_id: String,
     client_secret: String,
     grant_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
 }
 
 pub struct AuthClient {
     config: Config,
     http_client: ReqwestClient,
     current_token: Arc<Mutex<Option<AuthToken>>>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl AuthClient {
-    pub fn new(config: Config) -> Self {
+    /// `rate_limiter` is shared with the field client
+    /// so token requests count against the same budget
+    pub fn new(config: Config, rate_limiter: Arc<RateLimiter>) -> Self {
         Self {
             config,
-            http_client: ReqwestClient::new(),
+            http_client: retry::http_client(),
             current_token: Arc::new(Mutex::new(None)),
+            rate_limiter,
         }
     }
 
@@ -54,16 +72,34 @@ impl AuthClient {
         Ok(token_guard.as_ref().unwrap().header_value())
     }
 
+    /// discards the current token and fetches a new one
+    /// for when halo rejects a token before it was due to expire
+    pub async fn refresh_token(&self) -> anyhow::Result<String> {
+        let mut token_guard = self.current_token.lock().await;
+
+        let new_token = self.fetch_new_token().await?;
+        let header_value = new_token.header_value();
+        *token_guard = Some(new_token);
+
+        Ok(header_value)
+    }
+
     async fn fetch_new_token(&self) -> anyhow::Result<AuthToken> {
+        let credentials = self.config.credentials()?;
         let token_request = TokenRequest {
-            client_id: self.config.client_id.clone(),
-            client_secret: self.config.client_secret.clone(),
+            client_id: credentials.client_id.clone(),
+            client_secret: credentials.client_secret.clone(),
             grant_type: "client_credentials".to_string(),
+            scope: self.config.scope.clone(),
         };
 
+        self.rate_limiter.acquire().await;
         let response = self
             .http_client
-            .post(self.config.token_url.as_str())
+            .post(token_url(
+                &self.config.token_url,
+                self.config.tenant.as_deref(),
+            ))
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&token_request)
             .send()
@@ -71,16 +107,21 @@ impl AuthClient {
 
         let status = response.status();
 
-        if status == reqwest::StatusCode::UNAUTHORIZED {
-            anyhow::bail!("invalid credentials");
-        }
-
         let response_text = response
             .text()
             .await
             .map_err(|e| anyhow::anyhow!("failed to read response: {}", e))?;
+        let error_response = serde_json::from_str::<ErrorResponse>(&response_text).ok();
+
+        if let Some(error_response) = &error_response && error_response.is_scope_error() {
+            return Err(self.scope_error(error_response));
+        }
 
-        if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&response_text) {
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            anyhow::bail!("invalid credentials");
+        }
+
+        if let Some(error_response) = error_response {
             anyhow::bail!(
                 "{}: {}",
                 error_response.error,
@@ -97,4 +138,44 @@ impl AuthClient {
             token_response.expires_in,
         ))
     }
+
+    /// says whether the scope was refused or never requested
+    fn scope_error(&self, error_response: &ErrorResponse) -> anyhow::Error {
+        match &self.config.scope {
+            Some(scope) => anyhow::anyhow!(
+                "scope '{}' was refused by halo, check the scopes allowed for this api application: {}",
+                scope,
+                error_response.error_description
+            ),
+            None => anyhow::anyhow!(
+                "no scope was requested but this api application needs one, set SCOPE: {}",
+                error_response.error_description
+            ),
+        }
+    }
+}
+
+/// halo's hosted auth server reads the tenant from the query string rather than the form
+fn token_url(token_url: &Url, tenant: Option<&str>) -> Url {
+    let mut token_url = token_url.clone();
+    if let Some(tenant) = tenant {
+        token_url.query_pairs_mut().append_pair("tenant", tenant);
+    }
+    token_url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenant_is_sent_in_the_query_string() {
+        let url = Url::parse("https://example.com/auth/token").unwrap();
+
+        assert_eq!(
+            token_url(&url, Some("acme corp")).as_str(),
+            "https://example.com/auth/token?tenant=acme+corp"
+        );
+        assert_eq!(token_url(&url, None), url);
+    }
 }
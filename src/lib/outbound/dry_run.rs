@@ -0,0 +1,134 @@
+use anyhow::Context;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+const AUTHORIZATION_HEADER: &str = "Authorization";
+const REDACTED: &str = "<redacted>";
+
+/// a request exactly as it would have been sent to halo
+/// with the auth token redacted
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunRequest {
+    pub method: String,
+    pub endpoint: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+/// collects requests in place of sending them
+#[derive(Debug, Default)]
+pub struct DryRunRecorder {
+    requests: Mutex<Vec<DryRunRequest>>,
+}
+
+impl DryRunRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(
+        &self,
+        method: &str,
+        endpoint: &str,
+        headers: &[(&str, String)],
+        body: &impl Serialize,
+    ) -> anyhow::Result<()> {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| {
+                // header names are case insensitive, and reqwest spells them in lowercase
+                let value = if name.eq_ignore_ascii_case(AUTHORIZATION_HEADER) {
+                    redact_token(value)
+                } else {
+                    value.clone()
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        let request = DryRunRequest {
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            headers,
+            body: serde_json::to_value(body).context("failed to serialize request body")?,
+        };
+
+        self.requests
+            .lock()
+            .map_err(|_| anyhow::anyhow!("dry run recorder lock poisoned"))?
+            .push(request);
+
+        Ok(())
+    }
+
+    pub fn requests(&self) -> Vec<DryRunRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// keeps the token type so the header shape stays visible
+fn redact_token(header_value: &str) -> String {
+    match header_value.split_whitespace().next() {
+        Some(token_type) if header_value.contains(' ') => format!("{} {}", token_type, REDACTED),
+        _ => REDACTED.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "tok1";
+
+    fn recorded_with(header_name: &str) -> String {
+        let recorder = DryRunRecorder::new();
+        recorder
+            .record(
+                "POST",
+                "https://halo.example.com/api/fieldinfo",
+                &[(header_name, format!("Bearer {}", TOKEN))],
+                &serde_json::json!({ "name": "CFTest" }),
+            )
+            .unwrap();
+        serde_json::to_string(&recorder.requests()).unwrap()
+    }
+
+    #[test]
+    fn redacts_token_whatever_the_header_case() {
+        for header_name in ["Authorization", "authorization", "AUTHORIZATION"] {
+            let recorded = recorded_with(header_name);
+            assert!(
+                !recorded.contains(TOKEN),
+                "{} leaked: {}",
+                header_name,
+                recorded
+            );
+            assert!(recorded.contains("Bearer <redacted>"));
+        }
+    }
+
+    #[test]
+    fn redacts_token_sent_by_the_field_client() {
+        let recorded = recorded_with(reqwest::header::AUTHORIZATION.as_str());
+        assert!(!recorded.contains(TOKEN));
+    }
+
+    #[test]
+    fn keeps_other_headers() {
+        let recorder = DryRunRecorder::new();
+        recorder
+            .record(
+                "GET",
+                "https://halo.example.com/api/fieldinfo",
+                &[("content-type", "application/json".to_string())],
+                &serde_json::Value::Null,
+            )
+            .unwrap();
+        let requests = recorder.requests();
+        assert_eq!(requests[0].headers["content-type"], "application/json");
+    }
+}
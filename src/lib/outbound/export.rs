@@ -0,0 +1,44 @@
+use anyhow::Context;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::domain::models::custom_field::CustomField;
+use crate::outbound::dry_run::DryRunRequest;
+use crate::outbound::http_custom_field::HttpCustomField;
+
+/// writes the payloads halo would receive for each field
+/// to the given file or stdout when none is given
+pub fn write_payloads(fields: &[CustomField], output: Option<&Path>) -> anyhow::Result<()> {
+    let payloads = fields
+        .iter()
+        .map(HttpCustomField::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    write_json(&payloads, output)
+}
+
+/// writes every request recorded during a dry run
+/// to the given file or stdout when none is given
+pub fn write_dry_run_requests(
+    requests: &[DryRunRequest],
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    write_json(requests, output)
+}
+
+fn write_json<T: Serialize + ?Sized>(value: &T, output: Option<&Path>) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value).context("failed to serialize json")?;
+
+    match output {
+        Some(path) => fs::write(path, json)
+            .with_context(|| format!("failed to write json to {}", path.display()))?,
+        None => {
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "{}", json).context("failed to write json to stdout")?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,65 +1,346 @@
 use anyhow::Context;
-use log::debug;
-use reqwest::Client as ReqwestClient;
-use std::time::Duration;
+use log::{debug, info};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Client as ReqwestClient, RequestBuilder, Response, StatusCode};
+use std::sync::Arc;
 use tokio::time::sleep;
 
 use crate::config::Config;
 use crate::domain::models::custom_field::CustomField;
-use crate::outbound::http_custom_field::HttpCustomField;
+use crate::domain::models::existing_field::ExistingField;
+use crate::outbound::auth::client::AuthClient;
+use crate::outbound::dry_run::{DryRunRecorder, DryRunRequest};
+use crate::outbound::http_custom_field::{HttpCustomField, HttpExistingField};
+use crate::outbound::rate_limiter::RateLimiter;
+use crate::outbound::retry::{self, RequestError, RetryPolicy};
+
+const FIELD_INFO_PATH: &str = "fieldinfo";
 
 pub struct FieldClient {
     config: Config,
     http_client: ReqwestClient,
-    auth_token: String,
+    auth_client: Option<AuthClient>,
+    dry_run: Option<DryRunRecorder>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl FieldClient {
-    pub fn new(config: Config, auth_token: String) -> Self {
+    /// asks `auth_client` for a valid token before every request
+    pub fn new(config: Config, auth_client: AuthClient, rate_limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            retry_policy: RetryPolicy::new(config.max_attempts),
+            config,
+            http_client: retry::http_client(),
+            auth_client: Some(auth_client),
+            dry_run: None,
+            rate_limiter,
+        }
+    }
+
+    /// records requests instead of sending them
+    /// authentication is optional since nothing reaches halo
+    pub fn dry_run(
+        config: Config,
+        auth_client: Option<AuthClient>,
+        rate_limiter: Arc<RateLimiter>,
+    ) -> Self {
         Self {
+            retry_policy: RetryPolicy::new(config.max_attempts),
             config,
-            http_client: ReqwestClient::new(),
-            auth_token,
+            http_client: retry::http_client(),
+            auth_client,
+            dry_run: Some(DryRunRecorder::new()),
+            rate_limiter,
         }
     }
 
-    pub async fn create_field(&self, custom_field: &CustomField) -> anyhow::Result<()> {
-        // rate limiting: 500ms delay between requests
-        // max 120 requests/minute, staying under the 700/5min limit
-        sleep(Duration::from_millis(500)).await;
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    pub fn dry_run_requests(&self) -> Vec<DryRunRequest> {
+        self.dry_run
+            .as_ref()
+            .map(|recorder| recorder.requests())
+            .unwrap_or_default()
+    }
+
+    /// the current token, refreshed first when it is about to expire
+    async fn auth_header(&self) -> anyhow::Result<Option<String>> {
+        match &self.auth_client {
+            Some(auth_client) => Ok(Some(auth_client.get_valid_token().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// headers as recorded by a dry run
+    async fn headers(&self) -> Result<Vec<(&'static str, String)>, RequestError> {
+        let auth_header = self.auth_header().await.map_err(|e| RequestError {
+            message: format!("authentication failed: {}", e),
+            attempts: 0,
+        })?;
+
+        Ok(vec![
+            (AUTHORIZATION.as_str(), auth_header.unwrap_or_default()),
+            (CONTENT_TYPE.as_str(), "application/json".to_string()),
+        ])
+    }
 
-        let endpoint = format!("{}/fieldinfo", self.config.api_url);
-        let http_custom_field = HttpCustomField::from(custom_field);
+    fn endpoint(&self) -> String {
+        Config::join_path(&self.config.api_url, FIELD_INFO_PATH).to_string()
+    }
+
+    /// lists every custom field on the instance
+    /// this only reads, so it is sent during dry runs when a token is available
+    pub async fn list_fields(&self) -> anyhow::Result<Vec<ExistingField>> {
+        if !self.can_list_fields() {
+            anyhow::bail!("listing existing fields requires authentication");
+        }
+
+        debug!("sending field list request");
+
+        let request = self.http_client.get(self.endpoint());
+        let (response, _) = self
+            .send(request, "listing existing fields failed", None)
+            .await?;
+        let response = response.context("listing existing fields returned no response")?;
+
+        let existing_fields: Vec<HttpExistingField> = response
+            .json()
+            .await
+            .context("failed to parse existing fields")?;
+
+        Ok(existing_fields
+            .into_iter()
+            .map(ExistingField::from)
+            .collect())
+    }
+
+    pub fn can_list_fields(&self) -> bool {
+        self.auth_client.is_some()
+    }
+
+    /// returns how many attempts the request took
+    pub async fn create_field(&self, custom_field: &CustomField) -> Result<u32, RequestError> {
+        let http_custom_field =
+            HttpCustomField::try_from(custom_field).map_err(|e| RequestError {
+                message: e.to_string(),
+                attempts: 0,
+            })?;
+        self.post_field(http_custom_field, custom_field, "creation", true)
+            .await
+    }
+
+    /// pushes label and input type changes onto field `id`
+    /// and appends `added_options` to its selection values
+    pub async fn update_field(
+        &self,
+        id: i64,
+        custom_field: &CustomField,
+        added_options: &[String],
+    ) -> Result<u32, RequestError> {
+        let http_custom_field = HttpCustomField::update(id, custom_field, added_options);
+        self.post_field(http_custom_field, custom_field, "update", false)
+            .await
+    }
+
+    pub async fn delete_field(&self, id: i64, name: &str) -> Result<u32, RequestError> {
+        let endpoint = format!("{}/{}", self.endpoint(), id);
+
+        if let Some(recorder) = &self.dry_run {
+            debug!("recording dry run deletion request for: {}", name);
+            return Self::record(recorder, "DELETE", &endpoint, &self.headers().await?, &());
+        }
+
+        debug!("sending field deletion request for: {}", name);
+
+        let request = self
+            .http_client
+            .delete(&endpoint)
+            .header(CONTENT_TYPE, "application/json");
+
+        let (_, attempts) = self
+            .send(
+                request,
+                &format!("field deletion failed for '{}'", name),
+                None,
+            )
+            .await?;
+
+        Ok(attempts)
+    }
+
+    /// halo creates fields without an id and updates fields with one
+    async fn post_field(
+        &self,
+        http_custom_field: HttpCustomField,
+        custom_field: &CustomField,
+        operation: &str,
+        creating: bool,
+    ) -> Result<u32, RequestError> {
+        let endpoint = self.endpoint();
         // halo api requires an array of custom field update objects
         let wrapped_http_custom_field = vec![http_custom_field];
 
-        debug!("sending field creation request for: {}", custom_field.label);
+        if let Some(recorder) = &self.dry_run {
+            debug!(
+                "recording dry run {} request for: {}",
+                operation, custom_field.label
+            );
+            return Self::record(
+                recorder,
+                "POST",
+                &endpoint,
+                &self.headers().await?,
+                &wrapped_http_custom_field,
+            );
+        }
+
+        debug!(
+            "sending field {} request for: {}",
+            operation, custom_field.label
+        );
 
-        let response = self
-            .http_client
-            .post(&endpoint)
-            .header("Authorization", &self.auth_token)
-            .header("Content-Type", "application/json")
-            .json(&wrapped_http_custom_field)
-            .send()
-            .await
-            .context("failed to send request")?;
+        let request = self.http_client.post(&endpoint);
+
+        let failure = format!("field {} failed for '{}'", operation, custom_field.label);
+        let (_, attempts) = self
+            .send(
+                request.json(&wrapped_http_custom_field),
+                &failure,
+                creating.then_some(custom_field),
+            )
+            .await?;
+
+        Ok(attempts)
+    }
+
+    /// a recorded request counts as a single attempt
+    fn record(
+        recorder: &DryRunRecorder,
+        method: &str,
+        endpoint: &str,
+        headers: &[(&str, String)],
+        body: &impl serde::Serialize,
+    ) -> Result<u32, RequestError> {
+        recorder
+            .record(method, endpoint, headers, body)
+            .map(|_| 1)
+            .map_err(|e| RequestError {
+                message: e.to_string(),
+                attempts: 0,
+            })
+    }
+
+    /// sends `request` with a valid token once the rate limiter allows it
+    /// retrying transient failures under the retry policy
+    /// and a rejected token once after forcing a refresh
+    /// `failure` prefixes the error once every attempt is used up
+    /// the response is none when `creating` timed out but is found on the instance,
+    /// since sending it again would create a second field under a suffixed name
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        failure: &str,
+        creating: Option<&CustomField>,
+    ) -> Result<(Option<Response>, u32), RequestError> {
+        let mut attempt = 0;
+        let mut refreshed = false;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
+        loop {
+            attempt += 1;
+            let fail = |message: String| RequestError {
+                message: format!("{}: {}", failure, message),
+                attempts: attempt,
+            };
+
+            let mut pending = request
+                .try_clone()
+                .ok_or_else(|| fail("request cannot be retried".to_string()))?;
+            if let Some(auth_header) = self
+                .auth_header()
                 .await
-                .unwrap_or_else(|_| "failed to get error response".to_string());
+                .map_err(|e| fail(format!("authentication failed: {}", e)))?
+            {
+                pending = pending.header(AUTHORIZATION, auth_header);
+            }
+
+            self.rate_limiter.acquire().await;
+            let (reason, retry_after) = match pending.send().await {
+                Ok(response) if response.status().is_success() => {
+                    self.rate_limiter.relax().await;
+                    return Ok((Some(response), attempt));
+                }
+                Ok(response) if response.status() == StatusCode::UNAUTHORIZED && !refreshed => {
+                    let Some(auth_client) = &self.auth_client else {
+                        return Err(fail("status 401 Unauthorized".to_string()));
+                    };
+                    info!("↻ token rejected by halo, refreshing and retrying");
+                    auth_client
+                        .refresh_token()
+                        .await
+                        .map_err(|e| fail(format!("token refresh failed: {}", e)))?;
+                    refreshed = true;
+                    continue;
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        self.rate_limiter.throttle().await;
+                    }
+                    if !retry::is_transient_status(status)
+                        || !self.retry_policy.allows_retry(attempt)
+                    {
+                        let error_text = response
+                            .text()
+                            .await
+                            .unwrap_or_else(|_| "failed to get error response".to_string());
+                        return Err(fail(format!("status {}, error: {}", status, error_text)));
+                    }
+                    (
+                        format!("status {}", status),
+                        retry::retry_after(response.headers()),
+                    )
+                }
+                Err(e) => {
+                    if !retry::is_transient_error(&e) || !self.retry_policy.allows_retry(attempt) {
+                        return Err(fail(format!("failed to send request: {}", e)));
+                    }
+                    if let Some(custom_field) = creating
+                        && e.is_timeout()
+                        && self
+                            .was_created(custom_field)
+                            .await
+                            .map_err(|e| fail(format!("checking for the field failed: {:#}", e)))?
+                    {
+                        info!(
+                            "✓ {} was created before the request timed out, not sending it again",
+                            custom_field.label
+                        );
+                        return Ok((None, attempt));
+                    }
+                    (e.to_string(), None)
+                }
+            };
 
-            anyhow::bail!(
-                "field creation failed for '{}': status {}, error: {}",
-                custom_field.label,
-                status,
-                error_text
+            let delay = self.retry_policy.delay(attempt, retry_after);
+            info!(
+                "↻ {} (attempt {} of {}), retrying in {:.1}s",
+                reason,
+                attempt,
+                self.retry_policy.max_attempts,
+                delay.as_secs_f64()
             );
+            sleep(delay).await;
         }
+    }
 
-        Ok(())
+    /// true when `custom_field` is already on the instance
+    async fn was_created(&self, custom_field: &CustomField) -> anyhow::Result<bool> {
+        let existing_fields = Box::pin(self.list_fields()).await?;
+        Ok(existing_fields
+            .iter()
+            .any(|existing| existing.matches(custom_field)))
     }
 }
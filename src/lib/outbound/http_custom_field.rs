@@ -1,9 +1,21 @@
-use crate::domain::models::custom_field::CustomField;
-use serde::Serialize;
+use crate::domain::models::custom_field::{
+    CustomField, InvalidCustomField,
+    field_type::{FieldType, type_reference::TypeReference},
+    label::Label,
+    name::Name,
+    selection_option::SelectionOptions,
+    settings::{FieldSettings, RequestedSettings},
+    usage::FieldUsage,
+};
+use crate::domain::models::existing_field::ExistingField;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Serialize)]
 pub struct HttpCustomField {
-    usage: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i64>,
     name: String,
     label: String,
     #[serde(rename = "type")]
@@ -11,9 +23,22 @@ pub struct HttpCustomField {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "inputtype")]
     input_type_id: Option<u8>,
+    /// halo appends these to the existing values
+    /// so updates only carry the options being added
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "new_values")]
     selection_options: Option<String>,
+    /// sent with updates too, since the same name can exist for another entity
+    usage: u8,
+    #[serde(flatten)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    settings: Option<HttpFieldSettings>,
+}
+
+/// only sent on creation
+/// so updates keep whatever is configured in halo
+#[derive(Serialize)]
+struct HttpFieldSettings {
     searchable: bool,
     user_searchable: bool,
     calendar_searchable: bool,
@@ -23,20 +48,211 @@ pub struct HttpCustomField {
     copy_to_child_on_update: bool,
 }
 
-impl From<&CustomField> for HttpCustomField {
-    fn from(value: &CustomField) -> Self {
+impl From<&FieldSettings> for HttpFieldSettings {
+    fn from(value: &FieldSettings) -> Self {
+        Self {
+            searchable: value.searchable,
+            user_searchable: value.user_searchable,
+            calendar_searchable: value.calendar_searchable,
+            copy_to_child: value.copy_to_child,
+            copy_to_child_on_update: value.copy_to_child_on_update,
+        }
+    }
+}
+
+impl HttpCustomField {
+    /// pushes the label and input type of `value` onto field `id`
+    /// adding `added_options` without touching existing values
+    pub fn update(id: i64, value: &CustomField, added_options: &[String]) -> Self {
         Self {
-            usage: 1,
+            id: Some(id),
+            name: value.name.to_string(),
+            label: value.label.to_string(),
+            type_id: value.field_type.field_type_id(),
+            input_type_id: value.field_type.input_type_id(),
+            selection_options: (!added_options.is_empty())
+                .then(|| FieldType::options_string(added_options)),
+            usage: value.settings.usage.id(),
+            settings: None,
+        }
+    }
+}
+
+/// `new_values` cannot say which option sits under which
+/// and no payload halo accepts for nested options has been confirmed
+#[derive(Debug, Error)]
+#[error(
+    "field '{name}' has nested tree options, which cannot be created yet, create the field in halo instead"
+)]
+pub struct NestedOptionsUnsupported {
+    pub name: String,
+}
+
+impl TryFrom<&CustomField> for HttpCustomField {
+    type Error = NestedOptionsUnsupported;
+
+    fn try_from(value: &CustomField) -> Result<Self, Self::Error> {
+        if value.field_type.option_tree().is_some() {
+            return Err(NestedOptionsUnsupported {
+                name: value.name.to_string(),
+            });
+        }
+
+        Ok(Self {
+            id: None,
             name: value.name.to_string(),
             label: value.label.to_string(),
             type_id: value.field_type.field_type_id(),
             input_type_id: value.field_type.input_type_id(),
             selection_options: value.field_type.selection_options_string(),
-            searchable: true,
-            user_searchable: true,
-            calendar_searchable: true,
-            copy_to_child: true,
-            copy_to_child_on_update: true,
+            usage: value.settings.usage.id(),
+            settings: Some(HttpFieldSettings::from(&value.settings)),
+        })
+    }
+}
+
+/// a custom field as listed by halo
+#[derive(Debug, Deserialize)]
+pub struct HttpExistingField {
+    id: i64,
+    name: String,
+    #[serde(default)]
+    label: String,
+    #[serde(rename = "type")]
+    type_id: u8,
+    #[serde(default)]
+    #[serde(rename = "inputtype")]
+    input_type_id: Option<u8>,
+    #[serde(default)]
+    values: Vec<HttpSelectionValue>,
+    #[serde(default)]
+    usage: Option<u8>,
+    #[serde(default)]
+    searchable: Option<bool>,
+    #[serde(default)]
+    user_searchable: Option<bool>,
+    #[serde(default)]
+    calendar_searchable: Option<bool>,
+    #[serde(default)]
+    #[serde(rename = "copytochild")]
+    copy_to_child: Option<bool>,
+    #[serde(default)]
+    #[serde(rename = "copytochildonupdate")]
+    copy_to_child_on_update: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpSelectionValue {
+    #[serde(default)]
+    id: i64,
+    #[serde(default)]
+    value: String,
+    /// set on the nested values of a tree dropdown
+    #[serde(default)]
+    parent_id: Option<i64>,
+}
+
+impl HttpExistingField {
+    /// the values from the top of the tree down to `selection_value`
+    /// a parent that cannot be found ends the path rather than failing the field
+    fn value_path(&self, selection_value: &HttpSelectionValue) -> Vec<String> {
+        let mut path = vec![selection_value.value.clone()];
+        let mut parent_id = selection_value.parent_id;
+        while let Some(parent) = parent_id
+            .filter(|parent_id| *parent_id != 0)
+            .and_then(|parent_id| self.values.iter().find(|value| value.id == parent_id))
+        {
+            // guards against values that are their own ancestors
+            if path.len() > self.values.len() {
+                break;
+            }
+            path.insert(0, parent.value.clone());
+            parent_id = parent.parent_id;
+        }
+        path
+    }
+
+    fn custom_field(&self) -> Result<CustomField, InvalidCustomField> {
+        let selection_options = SelectionOptions::existing(
+            self.values
+                .iter()
+                .map(|selection_value| self.value_path(selection_value))
+                .collect(),
+        );
+
+        let field_type = FieldType::new(self.type_id, self.input_type_id, selection_options)?;
+        let settings = FieldSettings::existing(
+            RequestedSettings {
+                usage: self.usage.map(TypeReference::Id),
+                searchable: self.searchable,
+                user_searchable: self.user_searchable,
+                calendar_searchable: self.calendar_searchable,
+                copy_to_child: self.copy_to_child,
+                copy_to_child_on_update: self.copy_to_child_on_update,
+            },
+            &field_type,
+        );
+
+        Ok(CustomField {
+            name: Name::new(&self.name)?,
+            label: Label::new(&self.label)?,
+            field_type,
+            settings,
+        })
+    }
+}
+
+impl From<HttpExistingField> for ExistingField {
+    fn from(value: HttpExistingField) -> Self {
+        let field = value
+            .custom_field()
+            .inspect_err(|e| debug!("existing field '{}' cannot be modeled: {}", value.name, e))
+            .ok();
+
+        Self {
+            id: value.id,
+            name: value.name,
+            usage: value
+                .usage
+                .filter(|usage| *usage != 0)
+                .map(FieldUsage::from)
+                .unwrap_or_default(),
+            field,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_dropdown(options: &[&str]) -> CustomField {
+        CustomField::new(
+            "device",
+            "device",
+            TypeReference::Id(2),
+            Some(TypeReference::Name("tree".to_string())),
+            options.iter().map(|option| option.to_string()).collect(),
+            RequestedSettings::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn nested_options_are_refused() {
+        let field = tree_dropdown(&["Hardware > Laptop", "Software"]);
+
+        assert!(HttpCustomField::try_from(&field).is_err());
+    }
+
+    #[test]
+    fn top_level_tree_options_are_sent_as_new_values() {
+        let field = tree_dropdown(&["Hardware", "Software"]);
+
+        let payload = serde_json::to_value(HttpCustomField::try_from(&field).unwrap()).unwrap();
+
+        assert_eq!(payload["inputtype"], 1);
+        assert_eq!(payload["new_values"], "Hardware, Software");
+        assert!(payload.get("values").is_none());
+    }
+}
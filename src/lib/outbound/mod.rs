@@ -1,3 +1,7 @@
 pub mod auth;
+pub mod dry_run;
+pub mod export;
 pub mod field_client;
 pub mod http_custom_field;
+pub mod rate_limiter;
+pub mod retry;
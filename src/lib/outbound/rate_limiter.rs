@@ -0,0 +1,99 @@
+use log::info;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep};
+
+/// halo allows 700 requests per rolling 5 minutes
+/// the default leaves headroom for other integrations on the same instance
+pub const DEFAULT_REQUEST_BUDGET: u32 = 600;
+const WINDOW: Duration = Duration::from_secs(5 * 60);
+
+const MIN_THROTTLED_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_INTERVAL: Duration = Duration::from_secs(30);
+const RELAXED_INTERVAL: Duration = Duration::from_millis(50);
+
+/// rolling window limiter shared by every outbound request
+/// requests also keep a minimum spacing that grows when halo answers 429
+/// and shrinks back to nothing as requests succeed again
+#[derive(Debug)]
+pub struct RateLimiter {
+    budget: usize,
+    window: Duration,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    sent: VecDeque<Instant>,
+    interval: Duration,
+}
+
+impl RateLimiter {
+    /// allows `budget` requests in any 5 minute window
+    pub fn new(budget: u32) -> Self {
+        Self {
+            budget: budget.max(1) as usize,
+            window: WINDOW,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// waits until another request fits in the budget and claims its slot
+    /// waiting callers queue on the lock so slots go out in order
+    pub async fn acquire(&self) {
+        let mut state = self.state.lock().await;
+
+        loop {
+            let now = Instant::now();
+            while state
+                .sent
+                .front()
+                .is_some_and(|sent| now.duration_since(*sent) >= self.window)
+            {
+                state.sent.pop_front();
+            }
+
+            let window_ready = match state.sent.front() {
+                Some(oldest) if state.sent.len() >= self.budget => *oldest + self.window,
+                _ => now,
+            };
+            let spacing_ready = match state.sent.back() {
+                Some(last) => *last + state.interval,
+                None => now,
+            };
+            let ready = window_ready.max(spacing_ready);
+
+            if ready <= now {
+                state.sent.push_back(now);
+                return;
+            }
+
+            sleep(ready - now).await;
+        }
+    }
+
+    /// halo rate limited a request, so double the spacing
+    pub async fn throttle(&self) {
+        let mut state = self.state.lock().await;
+        state.interval = (state.interval * 2).clamp(MIN_THROTTLED_INTERVAL, MAX_INTERVAL);
+        info!(
+            "↓ rate limited by halo, slowing to one request every {:.1}s",
+            state.interval.as_secs_f64()
+        );
+    }
+
+    /// a request went through, so ease the spacing back off
+    pub async fn relax(&self) {
+        let mut state = self.state.lock().await;
+        if state.interval.is_zero() {
+            return;
+        }
+
+        state.interval = state.interval * 3 / 4;
+        if state.interval < RELAXED_INTERVAL {
+            state.interval = Duration::ZERO;
+            info!("↑ no longer rate limited, back to full speed");
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use std::time::Duration;
+use thiserror::Error;
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// how many times a transient failure is tried
+/// and how long to wait between tries
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ATTEMPTS)
+    }
+}
+
+impl RetryPolicy {
+    /// `max_attempts` counts the first try, so 1 disables retries
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: BASE_DELAY,
+            max_delay: MAX_DELAY,
+        }
+    }
+
+    pub fn allows_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// the wait after failed `attempt`
+    /// halo's retry-after wins up to `max_delay`, otherwise exponential backoff with full jitter
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16);
+        let ceiling = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        rand::thread_rng().gen_range(Duration::ZERO..=ceiling)
+    }
+}
+
+/// rate limiting, gateway errors and timeouts usually clear up on their own
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// a client that gives up on stalled requests, so they fail as timeouts and are retried
+pub fn http_client() -> Client {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .expect("http client cannot be built without a tls backend")
+}
+
+pub fn is_transient_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+/// reads retry-after given either in seconds or as an http date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// a request that failed for good
+/// `attempts` counts every try, including retries
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct RequestError {
+    pub message: String,
+    pub attempts: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn retry_after_is_used_when_short() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn retry_after_is_capped_at_max_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(3600))),
+            policy.max_delay
+        );
+    }
+
+    #[test]
+    fn backoff_stays_below_max_delay() {
+        let policy = RetryPolicy::default();
+        assert!(policy.delay(20, None) <= policy.max_delay);
+    }
+
+    #[test]
+    fn reads_retry_after_in_seconds_and_as_a_date() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        let date = (Utc::now() + chrono::Duration::days(1)).to_rfc2822();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1, retry_after(&headers)), policy.max_delay);
+    }
+}